@@ -0,0 +1,286 @@
+use std::{
+    fmt::Display,
+    io::{self, stdin, BufRead, StdinLock},
+};
+
+/// What had to be changed to bring an input into canonical form.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    pub crlf: usize,
+    pub trailing_whitespace: usize,
+    pub trailing_blank_lines: usize,
+    pub missing_final_newline: bool,
+}
+
+impl Report {
+    pub fn is_clean(&self) -> bool {
+        *self == Report::default()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if self.crlf > 0 {
+            parts.push(format!("{} CRLF line endings", self.crlf));
+        }
+        if self.trailing_whitespace > 0 {
+            parts.push(format!(
+                "{} lines with trailing whitespace",
+                self.trailing_whitespace
+            ));
+        }
+        if self.trailing_blank_lines > 0 {
+            parts.push(format!(
+                "{} trailing blank lines",
+                self.trailing_blank_lines
+            ));
+        }
+        if self.missing_final_newline {
+            parts.push("missing final newline".to_string());
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Canonical form: `\n` line endings, no trailing whitespace on any line, no blank lines at the
+/// end and exactly one final newline. Blank lines inside the input are kept as they are, since
+/// several days use them as separators.
+pub fn normalise(text: &str) -> (String, Report) {
+    let mut lines = Lines::new(text.as_bytes());
+    let mut out = String::with_capacity(text.len());
+    for line in lines.by_ref() {
+        // Reading from a slice can't fail
+        out.push_str(&line.unwrap());
+        out.push('\n');
+    }
+    (out, lines.report)
+}
+
+/// Drop-in replacement for `stdin().lines()` that normalises the input while streaming it and
+/// warns on stderr about anything that was changed. A caller that stops early is only warned
+/// about the part it read.
+pub fn lines() -> Lines {
+    let mut lines = Lines::new(stdin().lock());
+    lines.warn = true;
+    lines
+}
+
+/// Normalising line iterator over any reader. Only blank lines are held back, until it is known
+/// whether they are followed by more content, so arbitrarily large inputs can be streamed.
+pub struct Lines<R: BufRead = StdinLock<'static>> {
+    reader: R,
+    report: Report,
+    warn: bool,
+    keep_spaces: bool,
+    blank_run: usize,
+    held: Option<String>,
+    done: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(reader: R) -> Self {
+        Lines {
+            reader,
+            report: Report::default(),
+            warn: false,
+            keep_spaces: false,
+            blank_run: 0,
+            held: None,
+            done: false,
+        }
+    }
+
+    /// Leaves trailing spaces alone, for inputs that pad their lines to a fixed width. Other
+    /// trailing whitespace is still removed.
+    pub fn keep_trailing_spaces(mut self) -> Self {
+        self.keep_spaces = true;
+        self
+    }
+
+    /// Everything normalised so far. Only complete once the iterator has been exhausted.
+    pub fn report(&self) -> Report {
+        self.report
+    }
+
+    fn read_line(&mut self) -> Option<io::Result<String>> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(e) => return Some(Err(e)),
+        }
+
+        match line.strip_suffix('\n') {
+            Some(l) => line.truncate(l.len()),
+            None => self.report.missing_final_newline = true,
+        }
+        if line.ends_with('\r') {
+            line.pop();
+            self.report.crlf += 1;
+        }
+        let trimmed_len = if self.keep_spaces {
+            line.trim_end_matches(|c: char| c.is_whitespace() && c != ' ')
+                .len()
+        } else {
+            line.trim_end().len()
+        };
+        if trimmed_len != line.len() {
+            line.truncate(trimmed_len);
+            self.report.trailing_whitespace += 1;
+        }
+        Some(Ok(line))
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        self.report.trailing_blank_lines = self.blank_run;
+        // An unterminated last line that was blank is dropped entirely, there's nothing to add a
+        // newline to
+        if self.blank_run > 0 {
+            self.report.missing_final_newline = false;
+        }
+        self.blank_run = 0;
+        if self.warn && !self.report.is_clean() {
+            eprintln!("warning: normalised input: {}", self.report);
+        }
+    }
+}
+
+// The rest of an input the caller stopped reading early may be huge or never end, so it's left
+// unread
+impl<R: BufRead> Drop for Lines<R> {
+    fn drop(&mut self) {
+        if self.warn && !self.done && !self.report.is_clean() {
+            eprintln!("warning: normalised input so far: {}", self.report);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Flush blank lines that turned out to be followed by content
+        if self.held.is_some() {
+            if self.blank_run > 0 {
+                self.blank_run -= 1;
+                return Some(Ok(String::new()));
+            }
+            return self.held.take().map(Ok);
+        }
+        if self.done {
+            return None;
+        }
+
+        loop {
+            match self.read_line() {
+                None => {
+                    self.finish();
+                    return None;
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(line)) if line.is_empty() => self.blank_run += 1,
+                Some(Ok(line)) => {
+                    if self.blank_run == 0 {
+                        return Some(Ok(line));
+                    }
+                    self.held = Some(line);
+                    return self.next();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_line_endings() {
+        let (text, report) = normalise("a\r\nb\r\n");
+        assert_eq!(text, "a\nb\n");
+        assert_eq!(
+            report,
+            Report {
+                crlf: 2,
+                ..Report::default()
+            }
+        );
+    }
+
+    #[test]
+    fn interior_blank_lines_are_kept_and_trailing_ones_dropped() {
+        let (text, report) = normalise("a\n\n\nb\n\n\n");
+        assert_eq!(text, "a\n\n\nb\n");
+        assert_eq!(
+            report,
+            Report {
+                trailing_blank_lines: 2,
+                ..Report::default()
+            }
+        );
+    }
+
+    #[test]
+    fn whitespace_only_unterminated_last_line() {
+        let (text, report) = normalise("a\n  \t");
+        assert_eq!(text, "a\n");
+        assert_eq!(
+            report,
+            Report {
+                trailing_whitespace: 1,
+                trailing_blank_lines: 1,
+                ..Report::default()
+            }
+        );
+    }
+
+    #[test]
+    fn missing_final_newline() {
+        let (text, report) = normalise("a\nb");
+        assert_eq!(text, "a\nb\n");
+        assert_eq!(
+            report,
+            Report {
+                missing_final_newline: true,
+                ..Report::default()
+            }
+        );
+    }
+
+    #[test]
+    fn clean_input_is_unchanged() {
+        let (text, report) = normalise("a\n\nb\n");
+        assert_eq!(text, "a\n\nb\n");
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn report_after_stopping_early() {
+        let mut lines = Lines::new("a \r\nb\r\n\nc\t\nd\n\n".as_bytes());
+        assert_eq!(lines.next().unwrap().unwrap(), "a");
+        assert_eq!(lines.next().unwrap().unwrap(), "b");
+        assert_eq!(
+            lines.report(),
+            Report {
+                crlf: 2,
+                trailing_whitespace: 1,
+                ..Report::default()
+            }
+        );
+        // The blank line is held back until the line after it has been read
+        assert_eq!(lines.next().unwrap().unwrap(), "");
+        assert_eq!(lines.report().trailing_whitespace, 2);
+    }
+
+    #[test]
+    fn trailing_spaces_can_be_kept() {
+        let lines: Vec<String> = Lines::new("[A] \n 1  \t\n".as_bytes())
+            .keep_trailing_spaces()
+            .map(|l| l.unwrap())
+            .collect();
+        assert_eq!(lines, ["[A] ", " 1  "]);
+    }
+}
@@ -1,23 +1,320 @@
-use std::{io::stdin, ops::AddAssign};
+mod rebalance;
 
-fn main() {
-    let mut elves: Vec<u32> = Vec::new();
-    elves.push(0);
-    let mut cur_elf = elves.last_mut().unwrap();
-
-    let stdin = stdin();
-    for line in stdin.lines() {
-        if line.as_ref().expect("Read error").len() == 0 {
-            elves.push(0);
-            cur_elf = elves.last_mut().unwrap();
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    env,
+};
+
+use rebalance::Item;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+struct Elf {
+    // Position of the elf in the input, starting at 1
+    index: usize,
+    items: usize,
+    total: u32,
+    max_item: u32,
+}
+
+impl Elf {
+    fn mean(&self) -> f64 {
+        if self.items == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.items as f64
+        }
+    }
+}
+
+// Heavier elves are greater, on ties the one appearing first in the input wins
+impl Ord for Elf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total
+            .cmp(&other.total)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Elf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Groups the calorie lines into elves without holding more than the current elf in memory.
+fn elves(lines: impl Iterator<Item = String>) -> impl Iterator<Item = Elf> {
+    let mut lines = lines.peekable();
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        lines.peek()?;
+        index += 1;
+        let mut elf = Elf {
+            index,
+            ..Default::default()
+        };
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            let item = line.parse::<u32>().unwrap();
+            elf.items += 1;
+            elf.total += item;
+            elf.max_item = elf.max_item.max(item);
+        }
+        Some(elf)
+    })
+}
+
+/// Collects every elf's items, each identified by its line in the input.
+fn elf_items(lines: impl Iterator<Item = String>) -> Vec<Vec<Item>> {
+    let mut bins = vec![Vec::new()];
+    for (i, line) in lines.enumerate() {
+        if line.is_empty() {
+            bins.push(Vec::new());
             continue;
         }
+        bins.last_mut().unwrap().push(Item {
+            id: i + 1,
+            calories: line.parse().unwrap(),
+        });
+    }
+    bins
+}
 
-        cur_elf.add_assign(line.unwrap().parse::<u32>().unwrap());
+fn print_loads(label: &str, bins: &[Vec<Item>]) {
+    for (i, bin) in bins.iter().enumerate() {
+        println!(
+            "{} {:>4}: {:>8} ({} items)",
+            label,
+            i + 1,
+            bin.iter().map(|i| i.calories as u64).sum::<u64>(),
+            bin.len()
+        );
     }
+}
 
-    elves.sort();
-    elves.reverse();
-    println!("{}", elves[0]);
-    println!("{}", elves[0] + elves[1] + elves[2]);
+fn plan_rebalance() {
+    let mut bins = elf_items(aoc_input::lines().map(|l| l.expect("Read error")));
+    let max_before = bins
+        .iter()
+        .map(|b| b.iter().map(|i| i.calories as u64).sum::<u64>())
+        .max()
+        .unwrap();
+
+    let moves = rebalance::rebalance(&mut bins);
+    for m in &moves {
+        println!(
+            "move item on line {} ({} calories) from elf {} to elf {}",
+            m.item.id,
+            m.item.calories,
+            m.from + 1,
+            m.to + 1
+        );
+    }
+    print_loads("elf", &bins);
+    println!(
+        "Max load {} -> {} with {} moves (lower bound {})",
+        max_before,
+        bins.iter()
+            .map(|b| b.iter().map(|i| i.calories as u64).sum::<u64>())
+            .max()
+            .unwrap(),
+        moves.len(),
+        rebalance::lower_bound(&bins)
+    );
+}
+
+fn plan_groups(k: usize) {
+    let whole_elves = elves(aoc_input::lines().map(|l| l.expect("Read error")))
+        .map(|e| Item {
+            id: e.index,
+            calories: e.total,
+        })
+        .collect();
+
+    let groups = rebalance::partition(whole_elves, k);
+    for (i, group) in groups.iter().enumerate() {
+        let mut members: Vec<_> = group.iter().map(|e| e.id).collect();
+        members.sort_unstable();
+        println!(
+            "group {:>3}: {:>8} elves {:?}",
+            i + 1,
+            group.iter().map(|e| e.calories as u64).sum::<u64>(),
+            members
+        );
+    }
+    println!("Lower bound {}", rebalance::lower_bound(&groups));
+}
+
+/// Keeps the `n` heaviest elves seen so far in a bounded min-heap, sorted heaviest first.
+struct TopN {
+    n: usize,
+    heap: BinaryHeap<Reverse<Elf>>,
+}
+
+impl TopN {
+    fn new(n: usize) -> Self {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, elf: Elf) {
+        if self.heap.len() < self.n {
+            self.heap.push(Reverse(elf));
+        } else if self.heap.peek().is_some_and(|Reverse(min)| elf > *min) {
+            self.heap.pop();
+            self.heap.push(Reverse(elf));
+        }
+    }
+
+    fn into_sorted(self) -> Vec<Elf> {
+        // Sorting Reverse ascending gives the elves descending
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|r| r.0)
+            .collect()
+    }
+}
+
+struct Distribution {
+    // Sorted ascending
+    totals: Vec<u32>,
+}
+
+impl Distribution {
+    fn median(&self) -> f64 {
+        let len = self.totals.len();
+        if len % 2 == 1 {
+            self.totals[len / 2] as f64
+        } else {
+            (self.totals[len / 2 - 1] as f64 + self.totals[len / 2] as f64) / 2.0
+        }
+    }
+
+    // Nearest-rank percentile
+    fn percentile(&self, p: f64) -> u32 {
+        let rank = (p / 100.0 * self.totals.len() as f64).ceil() as usize;
+        self.totals[rank.clamp(1, self.totals.len()) - 1]
+    }
+
+    fn mean(&self) -> f64 {
+        self.totals.iter().map(|t| *t as f64).sum::<f64>() / self.totals.len() as f64
+    }
+
+    // Equal width buckets between the smallest and largest total, as (start, end, count)
+    fn histogram(&self, buckets: usize) -> Vec<(u32, u32, usize)> {
+        let min = self.totals[0];
+        let max = *self.totals.last().unwrap();
+        let width = ((max - min) as usize / buckets + 1) as u32;
+        let mut counts = vec![0; buckets];
+        for total in &self.totals {
+            counts[((total - min) / width) as usize] += 1;
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let start = min + i as u32 * width;
+                (start, start + width - 1, c)
+            })
+            .collect()
+    }
+}
+
+fn main() {
+    let mut top = 3;
+    let mut show_top = false;
+    let mut show_stats = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "top" => {
+                top = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("top needs a number of elves");
+                show_top = true;
+            }
+            "stats" => show_stats = true,
+            "rebalance" => return plan_rebalance(),
+            "groups" => {
+                let k = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|k| *k > 0)
+                    .expect("groups needs a number of groups");
+                return plan_groups(k);
+            }
+            _ => panic!(
+                "Unknown argument {}, expected top N, stats, rebalance or groups K",
+                arg
+            ),
+        }
+    }
+
+    let mut heaviest = TopN::new(top.max(3));
+    let mut totals = Vec::new();
+    for elf in elves(aoc_input::lines().map(|l| l.expect("Read error"))) {
+        heaviest.push(elf);
+        if show_stats {
+            totals.push(elf.total);
+        }
+    }
+    let heaviest = heaviest.into_sorted();
+
+    if !show_top && !show_stats {
+        println!("{}", heaviest[0].total);
+        println!("{}", heaviest.iter().take(3).map(|e| e.total).sum::<u32>());
+        return;
+    }
+
+    if show_top {
+        println!(
+            "{:>5} {:>7} {:>6} {:>8} {:>10} {:>8}",
+            "rank", "elf", "items", "total", "mean", "max"
+        );
+        for (rank, elf) in heaviest.iter().take(top).enumerate() {
+            println!(
+                "{:>5} {:>7} {:>6} {:>8} {:>10.1} {:>8}",
+                rank + 1,
+                elf.index,
+                elf.items,
+                elf.total,
+                elf.mean(),
+                elf.max_item
+            );
+        }
+        println!(
+            "Top {} total: {}",
+            top,
+            heaviest.iter().take(top).map(|e| e.total).sum::<u32>()
+        );
+    }
+
+    if show_stats && !totals.is_empty() {
+        totals.sort_unstable();
+        let dist = Distribution { totals };
+        println!("Elves:  {}", dist.totals.len());
+        println!("Mean:   {:.1}", dist.mean());
+        println!("Median: {:.1}", dist.median());
+        for p in [10.0, 25.0, 75.0, 90.0, 99.0] {
+            println!("P{:<5} {}", p, dist.percentile(p));
+        }
+
+        let histogram = dist.histogram(10);
+        let largest = histogram.iter().map(|b| b.2).max().unwrap();
+        for (start, end, count) in histogram {
+            println!(
+                "{:>8}-{:<8} {:>6} {}",
+                start,
+                end,
+                count,
+                "#".repeat(count * 50 / largest)
+            );
+        }
+    }
 }
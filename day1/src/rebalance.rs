@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+
+/// Something with calories that can be moved between bins, identified by `id`.
+#[derive(Clone, Copy)]
+pub struct Item {
+    pub id: usize,
+    pub calories: u32,
+}
+
+pub struct Move {
+    pub item: Item,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Moves single items, or swaps pairs of items, between the heaviest and the lightest bin for as
+/// long as that lowers the heavier of the two. Every step strictly decreases the sum of squared
+/// loads, so this always terminates.
+pub fn rebalance(bins: &mut [Vec<Item>]) -> Vec<Move> {
+    let mut loads: Vec<u64> = bins
+        .iter()
+        .map(|b| b.iter().map(|i| i.calories as u64).sum())
+        .collect();
+    let mut order: BTreeSet<(u64, usize)> = loads.iter().copied().zip(0..).collect();
+    let mut moves = Vec::new();
+
+    while order.len() > 1 {
+        let (high_load, from) = *order.last().unwrap();
+        let (low_load, to) = *order.first().unwrap();
+        let diff = high_load - low_load;
+
+        // The item closest to half the difference evens the pair out the most. When no single
+        // item fits, exchanging a heavier item for a lighter one can still close the gap.
+        let single = bins[from]
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| (i.calories as u64) < diff && i.calories > 0)
+            .min_by_key(|(_, i)| (2 * i.calories as u64).abs_diff(diff))
+            .map(|(p, i)| ((p, None), i.calories as u64));
+        let swap = || {
+            bins[from]
+                .iter()
+                .enumerate()
+                .flat_map(|(p, a)| bins[to].iter().enumerate().map(move |(q, b)| (p, a, q, b)))
+                .filter(|(_, a, _, b)| a.calories > b.calories)
+                .map(|(p, a, q, b)| ((p, Some(q)), (a.calories - b.calories) as u64))
+                .filter(|(_, shift)| *shift < diff)
+                .min_by_key(|(_, shift)| (2 * shift).abs_diff(diff))
+        };
+        let Some(((pos, back), shift)) = single.or_else(swap) else {
+            break;
+        };
+
+        order.remove(&(high_load, from));
+        order.remove(&(low_load, to));
+        loads[from] -= shift;
+        loads[to] += shift;
+        order.insert((loads[from], from));
+        order.insert((loads[to], to));
+
+        if let Some(q) = back {
+            let item = bins[to].swap_remove(q);
+            bins[from].push(item);
+            moves.push(Move {
+                item,
+                from: to,
+                to: from,
+            });
+        }
+        let item = bins[from].swap_remove(pos);
+        bins[to].push(item);
+        moves.push(Move { item, from, to });
+    }
+    moves
+}
+
+/// Largest-first assignment of items into `k` bins, followed by `rebalance`.
+pub fn partition(mut items: Vec<Item>, k: usize) -> Vec<Vec<Item>> {
+    items.sort_by_key(|i| std::cmp::Reverse(i.calories));
+    let mut bins: Vec<Vec<Item>> = vec![Vec::new(); k];
+    let mut loads = vec![0u64; k];
+    for item in items {
+        let lightest = (0..k).min_by_key(|b| loads[*b]).unwrap();
+        loads[lightest] += item.calories as u64;
+        bins[lightest].push(item);
+    }
+    rebalance(&mut bins);
+    bins
+}
+
+/// No arrangement can have a maximum load below the average load or the single largest item.
+pub fn lower_bound(bins: &[Vec<Item>]) -> u64 {
+    let total: u64 = bins.iter().flatten().map(|i| i.calories as u64).sum();
+    let largest = bins.iter().flatten().map(|i| i.calories).max().unwrap_or(0) as u64;
+    let n = bins.len().max(1) as u64;
+    total.div_ceil(n).max(largest)
+}
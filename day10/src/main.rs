@@ -1,5 +1,5 @@
 use std::{
-    io::{stdin, stdout, Write},
+    io::{stdout, Write},
     ops::AddAssign,
 };
 
@@ -28,7 +28,7 @@ fn main() {
     let mut sum_x = 0;
     let mut buffer = String::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(' ');
 
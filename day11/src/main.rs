@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::VecDeque, io::stdin, rc::Rc};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 #[derive(Clone)]
 struct Monkey {
@@ -41,7 +41,7 @@ fn main() {
     // in this vector. .0 test divisible by, .1 to monkey if test is true, .2 if false.
     let mut monkey_refs: Vec<(i32, usize, usize)> = Vec::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         if line.starts_with("Monkey") {
             // Starting items:
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, io::stdin};
+use std::collections::HashMap;
 
 fn vector_add(a: &(usize, usize), b: &(isize, isize)) -> Option<(usize, usize)> {
     let c = (a.0.checked_add_signed(b.0), a.1.checked_add_signed(b.1));
@@ -56,7 +56,7 @@ fn main() {
     let mut start_pos: (usize, usize) = (0, 0);
     let mut end_pos: (usize, usize) = (0, 0);
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         map.push(
             line.bytes()
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, io::stdin};
+use std::cmp::Ordering;
 
 #[derive(PartialEq, Eq, Clone)]
 enum Packet {
@@ -80,7 +80,7 @@ fn parse_line(line: &str) -> (Packet, usize) {
 }
 
 fn main() {
-    let lines: Vec<String> = stdin().lines().map(|l| l.unwrap()).collect();
+    let lines: Vec<String> = aoc_input::lines().map(|l| l.unwrap()).collect();
 
     // Sum index+1 of all ordered pairs
     let num = lines
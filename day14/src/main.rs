@@ -1,7 +1,7 @@
 #![feature(is_some_and)]
 #![feature(iter_next_chunk)]
 
-use std::{cmp::max, io::stdin};
+use std::cmp::max;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Material {
@@ -171,7 +171,7 @@ fn vector_normalise(a: &(isize, isize)) -> (isize, isize) {
 fn create_map() -> Map {
     let mut map: Map = Map::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let vertices: Vec<(isize, isize)> = line
             .split(" -> ")
@@ -1,7 +1,6 @@
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    io::stdin,
     ops::Range,
 };
 
@@ -115,7 +114,7 @@ fn main() {
     let mut sensors: Vec<Sensor> = Vec::new();
     let mut known_beacons: HashSet<(isize, isize)> = HashSet::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(['=', ',', ':']);
         let s = (
@@ -1,6 +1,5 @@
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
-    io::stdin,
     sync::mpsc,
     thread,
     time::{Duration, Instant},
@@ -19,7 +18,7 @@ fn main() {
     let mut nodes: HashMap<usize, Node> = HashMap::new();
     let mut name_map: HashMap<String, usize> = HashMap::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.splitn(10, ' ');
         let name = split.nth(1).unwrap().to_string();
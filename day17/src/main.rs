@@ -4,7 +4,6 @@
 use std::{
     collections::{HashMap, VecDeque},
     fmt::{Debug, Display},
-    io::stdin,
     iter,
 };
 
@@ -336,7 +335,7 @@ fn main() {
         BoulderType::Square,
     ];
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         line.chars()
             .map(|c| match c {
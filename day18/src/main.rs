@@ -1,13 +1,12 @@
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    io::stdin,
 };
 
 fn main() {
     let mut set: HashSet<(isize, isize, isize)> = HashSet::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(',');
         let x = split.next().unwrap().parse::<isize>().unwrap();
@@ -2,7 +2,6 @@ use std::{
     cell::RefCell,
     cmp::max,
     collections::BTreeMap,
-    io::stdin,
     ops::{Add, AddAssign, Sub, SubAssign},
 };
 
@@ -198,7 +197,7 @@ impl Pruner {
 fn main() {
     let mut blueprints: BTreeMap<usize, Blueprint> = BTreeMap::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(' ');
         let id = split
@@ -0,0 +1,173 @@
+use crate::game::Game;
+
+/// A round of the strategy guide: the opponent's move and the raw second column symbol.
+pub type Round = (usize, char);
+
+pub struct Ranked {
+    pub label: String,
+    pub score: i32,
+}
+
+/// Branch and bound over assignments of the second column symbols to distinct moves, keeping
+/// the `keep` best. Each symbol's score for a move is summed over its rounds up front, and the
+/// best way to assign the symbols still left is known for every set of moves already used, so
+/// only assignments that can still make it in are ever extended.
+struct Search {
+    // Score of all rounds with a symbol if it meant a move
+    gain: Vec<Vec<i32>>,
+    // Best score of the symbols after the first `used.count_ones()` over the unused moves
+    completion: Vec<i32>,
+    keep: usize,
+    // Bit set of the moves assigned so far
+    used: u32,
+    assigned: Vec<usize>,
+    score: i32,
+    // Best assignments so far, best first
+    found: Vec<(i32, Vec<usize>)>,
+}
+
+impl Search {
+    fn assign(&mut self, sym: usize) {
+        if sym == self.gain.len() {
+            self.found.push((self.score, self.assigned.clone()));
+            // Stable, so equal scores keep the order they were found in
+            self.found.sort_by_key(|(score, _)| -score);
+            self.found.truncate(self.keep);
+            return;
+        }
+        // Only equal or better than the worst kept assignment can still make it in
+        let bound = self.score + self.completion[self.used as usize];
+        if self.found.len() == self.keep && bound <= self.found[self.keep - 1].0 {
+            return;
+        }
+        for m in 0..self.gain.len() {
+            if self.used & 1 << m != 0 {
+                continue;
+            }
+            self.used |= 1 << m;
+            self.assigned.push(m);
+            self.score += self.gain[sym][m];
+            self.assign(sym + 1);
+            self.score -= self.gain[sym][m];
+            self.assigned.pop();
+            self.used &= !(1 << m);
+        }
+    }
+}
+
+/// Scores the guide under the `keep` best assignments of the second column symbols to moves,
+/// and under the outcome interpretation, best first.
+pub fn rank_mappings(game: &Game, rounds: &[Round], keep: usize) -> Vec<Ranked> {
+    let symbols: Vec<char> = game.moves.iter().map(|m| m.own).collect();
+    let mut counts = vec![vec![0; game.len()]; game.len()];
+    for (op, c) in rounds {
+        let sym = symbols
+            .iter()
+            .position(|s| s == c)
+            .unwrap_or_else(|| panic!("Unknown symbol {}", c));
+        counts[sym][*op] += 1;
+    }
+    let gain: Vec<Vec<i32>> = counts
+        .iter()
+        .map(|by_op| {
+            (0..game.len())
+                .map(|me| {
+                    by_op
+                        .iter()
+                        .enumerate()
+                        .map(|(op, n)| n * game.round_score(me, op))
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+    let n = game.len();
+    assert!(
+        n <= 20,
+        "Ranking mappings supports at most 20 moves, got {}",
+        n
+    );
+    // Filled from the full set down, since removing a move from a set makes it smaller
+    let mut completion = vec![0; 1 << n];
+    for used in (0..(1usize << n) - 1).rev() {
+        let sym = used.count_ones() as usize;
+        completion[used] = (0..n)
+            .filter(|m| used & 1 << m == 0)
+            .map(|m| gain[sym][m] + completion[used | 1 << m])
+            .max()
+            .unwrap();
+    }
+    let mut search = Search {
+        gain,
+        completion,
+        keep,
+        used: 0,
+        assigned: Vec::new(),
+        score: 0,
+        found: Vec::new(),
+    };
+    if keep > 0 {
+        search.assign(0);
+    }
+
+    let mut ranked: Vec<Ranked> = search
+        .found
+        .into_iter()
+        .map(|(score, assigned)| {
+            let label = symbols
+                .iter()
+                .zip(&assigned)
+                .map(|(s, m)| format!("{}={}", s, game.moves[*m].name))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Ranked { label, score }
+        })
+        .collect();
+
+    let score = rounds
+        .iter()
+        .map(|(op, c)| {
+            let offset = game
+                .outcome(*c)
+                .unwrap_or_else(|| panic!("Unknown symbol {}", c));
+            game.round_score(game.rev_resolve(*op, offset), *op)
+        })
+        .sum();
+    let label = game
+        .outcomes
+        .iter()
+        .map(|(s, o)| format!("{}={:+}", s, o))
+        .collect::<Vec<_>>()
+        .join(" ");
+    ranked.push(Ranked {
+        label: format!("outcome {}", label),
+        score,
+    });
+
+    ranked.sort_by_key(|r| -r.score);
+    ranked
+}
+
+/// Expected score of each move when played against opponents drawn from `freqs`, best first.
+pub fn best_fixed_moves(game: &Game, freqs: &[f64]) -> Vec<(usize, f64)> {
+    let mut expected: Vec<(usize, f64)> = (0..game.len())
+        .map(|me| {
+            let score = (0..game.len())
+                .map(|op| freqs[op] * game.round_score(me, op) as f64)
+                .sum();
+            (me, score)
+        })
+        .collect();
+    expected.sort_by(|a, b| b.1.total_cmp(&a.1));
+    expected
+}
+
+/// Relative frequency of each opponent move in the guide.
+pub fn opponent_frequencies(game: &Game, rounds: &[Round]) -> Vec<f64> {
+    let mut counts = vec![0usize; game.len()];
+    for (op, _) in rounds {
+        counts[*op] += 1;
+    }
+    let total = rounds.len().max(1) as f64;
+    counts.into_iter().map(|c| c as f64 / total).collect()
+}
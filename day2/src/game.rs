@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+
+pub struct Move {
+    pub name: String,
+    pub opponent: char,
+    pub own: char,
+    pub score: i32,
+}
+
+/// An odd-sized cyclic hand game. Moves are listed in cycle order and each move beats the
+/// (N-1)/2 moves preceding it, so Rock, Paper, Scissors gives the usual rules.
+pub struct Game {
+    pub moves: Vec<Move>,
+    // Symbol and how many steps ahead of the opponent's move it tells us to play
+    pub outcomes: Vec<(char, i32)>,
+    pub win: i32,
+    pub draw: i32,
+    pub loss: i32,
+}
+
+impl Game {
+    pub fn rps() -> Game {
+        Game::parse(
+            "Rock A X 1\n\
+             Paper B Y 2\n\
+             Scissors C Z 3\n\
+             outcome X -1\n\
+             outcome Y 0\n\
+             outcome Z 1\n\
+             win 6\n\
+             draw 3\n\
+             loss 0\n",
+        )
+        .unwrap()
+    }
+
+    pub fn rpsls() -> Game {
+        Game::parse(
+            "Rock A V 1\n\
+             Spock B W 2\n\
+             Paper C X 3\n\
+             Lizard D Y 4\n\
+             Scissors E Z 5\n\
+             outcome V -2\n\
+             outcome W -1\n\
+             outcome X 0\n\
+             outcome Y 1\n\
+             outcome Z 2\n\
+             win 6\n\
+             draw 3\n\
+             loss 0\n",
+        )
+        .unwrap()
+    }
+
+    /// Reads a game definition. Every line is either a move `<name> <opponent symbol> <own
+    /// symbol> <score>`, `outcome <symbol> <offset>` or one of `win`, `draw`, `loss` followed by
+    /// its score. Empty lines and lines starting with `#` are ignored. Every own symbol needs an
+    /// outcome, each with a different offset.
+    pub fn parse(text: &str) -> Result<Game, String> {
+        let mut game = Game {
+            moves: Vec::new(),
+            outcomes: Vec::new(),
+            win: 6,
+            draw: 3,
+            loss: 0,
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            let err = |msg: &str| format!("line {}: {}", i + 1, msg);
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let symbol = |s: &str| {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(err(&format!("'{}' is not a single character", s))),
+                }
+            };
+            let number = |s: &str| {
+                s.parse::<i32>()
+                    .map_err(|_| err(&format!("'{}' is not a number", s)))
+            };
+
+            match fields.as_slice() {
+                [] => (),
+                [first, ..] if first.starts_with('#') => (),
+                ["outcome", sym, offset] => game.outcomes.push((symbol(sym)?, number(offset)?)),
+                ["win", score] => game.win = number(score)?,
+                ["draw", score] => game.draw = number(score)?,
+                ["loss", score] => game.loss = number(score)?,
+                [name, opponent, own, score] => game.moves.push(Move {
+                    name: name.to_string(),
+                    opponent: symbol(opponent)?,
+                    own: symbol(own)?,
+                    score: number(score)?,
+                }),
+                _ => return Err(err(&format!("can't understand '{}'", line))),
+            }
+        }
+
+        let n = game.moves.len() as i32;
+        if n < 3 || n % 2 == 0 {
+            return Err(format!("need an odd number of at least 3 moves, got {}", n));
+        }
+        for (i, a) in game.moves.iter().enumerate() {
+            for b in &game.moves[i + 1..] {
+                if a.opponent == b.opponent || a.own == b.own {
+                    return Err(format!("moves {} and {} share a symbol", a.name, b.name));
+                }
+            }
+        }
+        for (i, (sym, offset)) in game.outcomes.iter().enumerate() {
+            if offset.abs() > (n - 1) / 2 {
+                let reach = (n - 1) / 2;
+                return Err(format!(
+                    "outcome {} offset {} is outside -{}..={}",
+                    sym, offset, reach, reach
+                ));
+            }
+            if game.own_move(*sym).is_none() {
+                return Err(format!("outcome {} is not one of our symbols", sym));
+            }
+            for (other, other_offset) in &game.outcomes[i + 1..] {
+                if sym == other {
+                    return Err(format!("outcome {} is given twice", sym));
+                }
+                if offset == other_offset {
+                    return Err(format!(
+                        "outcomes {} and {} share offset {}",
+                        sym, other, offset
+                    ));
+                }
+            }
+        }
+        // The guide's second column is also read as outcomes, so each symbol needs one
+        if let Some(m) = game.moves.iter().find(|m| game.outcome(m.own).is_none()) {
+            return Err(format!("symbol {} has no outcome", m.own));
+        }
+        Ok(game)
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn opponent_move(&self, symbol: char) -> Option<usize> {
+        self.moves.iter().position(|m| m.opponent == symbol)
+    }
+
+    pub fn own_move(&self, symbol: char) -> Option<usize> {
+        self.moves.iter().position(|m| m.own == symbol)
+    }
+
+    pub fn outcome(&self, symbol: char) -> Option<i32> {
+        self.outcomes
+            .iter()
+            .find(|(s, _)| *s == symbol)
+            .map(|(_, o)| *o)
+    }
+
+    /// How move `a` fares against move `b`, `Greater` meaning `a` wins.
+    pub fn resolve(&self, a: usize, b: usize) -> Ordering {
+        let n = self.len();
+        match (a + n - b) % n {
+            0 => Ordering::Equal,
+            d if d <= (n - 1) / 2 => Ordering::Greater,
+            _ => Ordering::Less,
+        }
+    }
+
+    /// The move to play `offset` steps ahead of the opponent's move.
+    pub fn rev_resolve(&self, op: usize, offset: i32) -> usize {
+        (op as i32 + offset).rem_euclid(self.len() as i32) as usize
+    }
+
+    pub fn outcome_score(&self, outcome: Ordering) -> i32 {
+        match outcome {
+            Ordering::Greater => self.win,
+            Ordering::Equal => self.draw,
+            Ordering::Less => self.loss,
+        }
+    }
+
+    pub fn round_score(&self, me: usize, op: usize) -> i32 {
+        self.moves[me].score + self.outcome_score(self.resolve(me, op))
+    }
+}
@@ -1,52 +1,190 @@
-use std::io::stdin;
+mod analysis;
+mod game;
+mod tournament;
+
+use std::{env, fs, fs::File, io::BufReader};
+
+use analysis::Round;
+use game::Game;
+use tournament::Player;
 
 fn main() {
+    let mut game = Game::rps();
+    let mut analyse = false;
+    let mut freqs: Option<String> = None;
+    let mut top = 10;
+    let mut by_outcome = false;
+    let mut players: Vec<String> = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "game" => {
+                game = match args
+                    .next()
+                    .expect("game needs rps, rpsls or a file")
+                    .as_str()
+                {
+                    "rps" => Game::rps(),
+                    "rpsls" => Game::rpsls(),
+                    path => {
+                        let text = fs::read_to_string(path).expect("Can't read game file");
+                        Game::parse(&aoc_input::normalise(&text).0)
+                            .unwrap_or_else(|e| panic!("Invalid game {}: {}", path, e))
+                    }
+                }
+            }
+            "analyse" => analyse = true,
+            "top" => {
+                top = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("top needs a number of mappings")
+            }
+            "freq" => freqs = Some(args.next().expect("freq needs a list like A=0.5,B=0.2")),
+            "outcome" => by_outcome = true,
+            "tournament" => players.extend(args.by_ref()),
+            _ => panic!(
+                "Unknown argument {}, expected game <rps|rpsls|FILE>, analyse, top K, freq LIST, \
+                 outcome or tournament FILE...",
+                arg
+            ),
+        }
+    }
+
+    if !players.is_empty() {
+        return run_tournament(&game, &players, by_outcome);
+    }
+
+    let rounds = read_guide(&game, aoc_input::lines().map(|l| l.expect("Read error")));
+    if analyse {
+        let freqs = match freqs {
+            Some(list) => parse_frequencies(&game, &list),
+            None => analysis::opponent_frequencies(&game, &rounds),
+        };
+        return print_analysis(&game, &rounds, &freqs, top);
+    }
+
     let mut score_1 = 0;
     let mut score_2 = 0;
-    let mut lines = stdin().lines();
-    while let Some(Ok(line)) = lines.next() {
-        let op = match_input(line.chars().nth(0).unwrap());
+    for (op, col2) in rounds {
+        let me = match_input(&game, col2, Game::own_move);
+        let res = match_input(&game, col2, Game::outcome);
 
-        let char2 = line.chars().nth(2).unwrap();
-        let me = match_input(char2);
-        let res = match_input_res(char2);
-
-        score_1 += me + (resolve(me, op) + 1) * 3;
-        score_2 += rev_resolve(op, res) + (res + 1) * 3;
+        score_1 += game.round_score(me, op);
+        score_2 += game.round_score(game.rev_resolve(op, res), op);
     }
     println!("{}", score_1);
     println!("{}", score_2);
 }
 
-fn match_input(input: char) -> i32 {
-    match input {
-        'A' => 1,
-        'B' => 2,
-        'C' => 3,
-        'X' => 1,
-        'Y' => 2,
-        'Z' => 3,
-        _ => panic!(),
+fn read_guide(game: &Game, lines: impl Iterator<Item = String>) -> Vec<Round> {
+    lines
+        .map(|line| {
+            let (op, col2) = parse_round(&line);
+            (match_input(game, op, Game::opponent_move), col2)
+        })
+        .collect()
+}
+
+// Reads each guide as a player. The second column is taken as the player's own move, or with
+// `by_outcome` as the outcome to aim for against the move in the first column.
+fn run_tournament(game: &Game, paths: &[String], by_outcome: bool) {
+    let players: Vec<Player> = paths
+        .iter()
+        .map(|path| {
+            let file = File::open(path).unwrap_or_else(|e| panic!("Can't open {}: {}", path, e));
+            let lines = aoc_input::Lines::new(BufReader::new(file)).map(|l| l.expect("Read error"));
+            let moves = read_guide(game, lines)
+                .into_iter()
+                .map(|(op, col2)| {
+                    if by_outcome {
+                        game.rev_resolve(op, match_input(game, col2, Game::outcome))
+                    } else {
+                        match_input(game, col2, Game::own_move)
+                    }
+                })
+                .collect();
+            Player {
+                name: path.clone(),
+                moves,
+            }
+        })
+        .collect();
+
+    println!(
+        "{:>4}  {:<24} {:>4} {:>4} {:>4} {:>10}",
+        "rank", "player", "W", "D", "L", "score"
+    );
+    for (rank, (i, s)) in tournament::round_robin(game, &players).iter().enumerate() {
+        println!(
+            "{:>4}  {:<24} {:>4} {:>4} {:>4} {:>10}",
+            rank + 1,
+            players[*i].name,
+            s.wins,
+            s.draws,
+            s.losses,
+            s.score
+        );
+    }
+}
+
+fn print_analysis(game: &Game, rounds: &[Round], freqs: &[f64], top: usize) {
+    println!("{:>4} {:>8}  mapping", "rank", "score");
+    for (i, r) in analysis::rank_mappings(game, rounds, top)
+        .iter()
+        .enumerate()
+    {
+        println!("{:>4} {:>8}  {}", i + 1, r.score, r.label);
+    }
+
+    println!();
+    println!("Opponent frequencies:");
+    for (m, f) in game.moves.iter().zip(freqs) {
+        println!("  {:<10} {:.3}", m.name, f);
+    }
+
+    println!("Expected score per round of always playing one move:");
+    let fixed = analysis::best_fixed_moves(game, freqs);
+    for (me, score) in &fixed {
+        println!("  {:<10} {:.3}", game.moves[*me].name, score);
     }
+    // Without knowing the next move, nothing does better than the best single move
+    let (best, expected) = fixed[0];
+    println!(
+        "Best strategy: always play {}, expected {:.0} over {} rounds",
+        game.moves[best].name,
+        expected * rounds.len() as f64,
+        rounds.len()
+    );
 }
 
-fn match_input_res(input: char) -> i32 {
-    match input {
-        'X' => -1,
-        'Y' => 0,
-        'Z' => 1,
-        _ => panic!(),
+// Weights such as `A=2,B=1,C=1`, normalised to sum to one. Missing moves get weight zero.
+fn parse_frequencies(game: &Game, list: &str) -> Vec<f64> {
+    let mut freqs = vec![0.0; game.len()];
+    for entry in list.split(',') {
+        let (sym, weight) = entry
+            .split_once('=')
+            .unwrap_or_else(|| panic!("Expected SYMBOL=WEIGHT, got '{}'", entry));
+        let sym = sym.trim().chars().next().unwrap_or(' ');
+        let op = match_input(game, sym, Game::opponent_move);
+        freqs[op] = weight
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid weight '{}'", weight));
     }
+    let total: f64 = freqs.iter().sum();
+    assert!(total > 0.0, "Frequencies must not all be zero");
+    freqs.into_iter().map(|f| f / total).collect()
 }
 
-fn resolve(a: i32, b: i32) -> i32 {
-    match (a - b) % 3 {
-        -2 => 1,
-        2 => -1,
-        i => i,
+fn parse_round(line: &str) -> (char, char) {
+    let mut split = line.split_whitespace().map(|s| s.chars().next().unwrap());
+    match (split.next(), split.next()) {
+        (Some(a), Some(b)) => (a, b),
+        _ => panic!("Expected two symbols in '{}'", line),
     }
 }
 
-fn rev_resolve(op: i32, res: i32) -> i32 {
-    (op + res - 1).rem_euclid(3) + 1
+fn match_input<T>(game: &Game, input: char, lookup: fn(&Game, char) -> Option<T>) -> T {
+    lookup(game, input).unwrap_or_else(|| panic!("Unknown symbol {}", input))
 }
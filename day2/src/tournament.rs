@@ -0,0 +1,72 @@
+use std::cmp::Ordering;
+
+use crate::game::Game;
+
+pub struct Player {
+    pub name: String,
+    pub moves: Vec<usize>,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Standing {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub score: i64,
+}
+
+impl Standing {
+    /// League table order: most wins, then most draws, then the highest total score.
+    pub fn rank(&self, other: &Self) -> Ordering {
+        other
+            .wins
+            .cmp(&self.wins)
+            .then(other.draws.cmp(&self.draws))
+            .then(other.score.cmp(&self.score))
+    }
+}
+
+/// Plays the two guides against each other for as long as the shorter one lasts and returns
+/// both players' total scores.
+pub fn play_match(game: &Game, a: &Player, b: &Player) -> (i64, i64) {
+    a.moves
+        .iter()
+        .zip(&b.moves)
+        .fold((0, 0), |(sa, sb), (ma, mb)| {
+            (
+                sa + game.round_score(*ma, *mb) as i64,
+                sb + game.round_score(*mb, *ma) as i64,
+            )
+        })
+}
+
+/// Every player meets every other player once. The match goes to whoever scored more over it.
+/// Returns the standings in league table order, paired with the player's position in `players`.
+pub fn round_robin(game: &Game, players: &[Player]) -> Vec<(usize, Standing)> {
+    let mut standings = vec![Standing::default(); players.len()];
+    for i in 0..players.len() {
+        for j in i + 1..players.len() {
+            let (si, sj) = play_match(game, &players[i], &players[j]);
+            standings[i].score += si;
+            standings[j].score += sj;
+            match si.cmp(&sj) {
+                Ordering::Greater => {
+                    standings[i].wins += 1;
+                    standings[j].losses += 1;
+                }
+                Ordering::Less => {
+                    standings[i].losses += 1;
+                    standings[j].wins += 1;
+                }
+                Ordering::Equal => {
+                    standings[i].draws += 1;
+                    standings[j].draws += 1;
+                }
+            }
+        }
+    }
+
+    let mut table: Vec<_> = standings.into_iter().enumerate().collect();
+    table.sort_by(|a, b| a.1.rank(&b.1));
+    table
+}
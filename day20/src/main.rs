@@ -1,10 +1,10 @@
-use std::{fmt::Debug, io::stdin};
+use std::fmt::Debug;
 
 fn main() {
     let mut input_vec: Vec<_> = vec![];
     let mut placemnet_vec = vec![];
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         input_vec.push(line.parse::<isize>().unwrap());
     }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, io::stdin};
+use std::collections::HashMap;
 
 #[derive(Clone, Copy)]
 enum Operation {
@@ -23,7 +23,7 @@ fn main() {
     let mut num_monkeys = HashMap::new();
     let mut op_monkeys = HashMap::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(':');
         let name = split.next().unwrap().to_string();
@@ -1,4 +1,4 @@
-use std::{io::stdin, iter::Peekable};
+use std::iter::Peekable;
 
 #[derive(PartialEq, Eq)]
 enum Tile {
@@ -80,7 +80,7 @@ fn main() {
     let mut map: Vec<Vec<Tile>> = vec![];
     let mut path_string = String::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         // Empty line between map and path input
         if line.is_empty() {
@@ -2,7 +2,6 @@ use std::{
     cell::RefCell,
     cmp::{max, min},
     collections::{HashMap, HashSet},
-    io::stdin,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -53,7 +52,7 @@ struct Elf {
 fn main() {
     let mut elves: HashMap<(isize, isize), Elf> = HashMap::new();
 
-    let mut lines = stdin().lines().enumerate();
+    let mut lines = aoc_input::lines().enumerate();
     while let Some((y, Ok(line))) = lines.next() {
         for (x, c) in line.char_indices() {
             match c {
@@ -1,7 +1,6 @@
 use std::{
     cmp::max,
     collections::{HashMap, HashSet},
-    io::stdin,
 };
 
 #[derive(Clone, Copy)]
@@ -32,7 +31,7 @@ fn main() {
     let mut max_x = 0;
     let mut max_y = 0;
 
-    let mut lines = stdin().lines().enumerate().peekable();
+    let mut lines = aoc_input::lines().enumerate().peekable();
     while let Some((y, Ok(line))) = lines.next() {
         for (x, c) in line.char_indices() {
             max_x = max(x, max_x);
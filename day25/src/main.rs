@@ -1,9 +1,7 @@
-use std::io::stdin;
-
 fn main() {
     let mut sum = 0;
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         for (p, c) in line.chars().rev().enumerate() {
             match c {
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, fmt::Display};
+
+/// A set of items as a bitmask, bit `i` standing for the item at index `i` of a `PriorityTable`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ItemSet(pub u64);
+
+impl ItemSet {
+    pub const ALL: ItemSet = ItemSet(u64::MAX);
+
+    pub fn from_items(items: &[u8]) -> ItemSet {
+        ItemSet(items.iter().fold(0, |set, i| set | 1 << i))
+    }
+
+    pub fn intersect(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn indices(self) -> impl Iterator<Item = u8> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let i = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            Some(i)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum TableError {
+    Syntax(String),
+    Duplicate(char),
+    TooManyItems,
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Syntax(entry) => write!(
+                f,
+                "can't understand '{}', expected ITEM=PRIORITY or FIRST-LAST=PRIORITY",
+                entry
+            ),
+            TableError::Duplicate(c) => write!(f, "item '{}' is listed more than once", c),
+            TableError::TooManyItems => write!(f, "more than 64 items listed"),
+        }
+    }
+}
+
+/// Maps item symbols to priorities. Items get consecutive indices in the order they are listed,
+/// and ASCII items are looked up without hashing.
+pub struct PriorityTable {
+    ascii: [Option<u8>; 128],
+    other: HashMap<char, u8>,
+    symbols: Vec<char>,
+    priorities: Vec<u64>,
+}
+
+impl PriorityTable {
+    pub const DEFAULT: &'static str = "a-z=1,A-Z=27";
+
+    /// Entries are separated by commas or newlines. An entry is either `ITEM=PRIORITY` or a range
+    /// of characters `FIRST-LAST=PRIORITY` that get consecutive priorities.
+    pub fn parse(spec: &str) -> Result<PriorityTable, TableError> {
+        let mut table = PriorityTable {
+            ascii: [None; 128],
+            other: HashMap::new(),
+            symbols: Vec::new(),
+            priorities: Vec::new(),
+        };
+
+        for entry in spec.split([',', '\n']).map(str::trim) {
+            if entry.is_empty() {
+                continue;
+            }
+            let syntax = || TableError::Syntax(entry.to_string());
+            let (items, priority) = entry.rsplit_once('=').ok_or_else(syntax)?;
+            let priority = priority.trim().parse::<u64>().map_err(|_| syntax())?;
+
+            let chars: Vec<char> = items.trim().chars().collect();
+            let range = match chars.as_slice() {
+                [c] => *c..=*c,
+                [first, '-', last] if first <= last => *first..=*last,
+                _ => return Err(syntax()),
+            };
+            for (i, c) in range.enumerate() {
+                table.insert(c, priority + i as u64)?;
+            }
+        }
+        Ok(table)
+    }
+
+    fn insert(&mut self, item: char, priority: u64) -> Result<(), TableError> {
+        if self.index(item).is_some() {
+            return Err(TableError::Duplicate(item));
+        }
+        if self.symbols.len() == 64 {
+            return Err(TableError::TooManyItems);
+        }
+        let index = self.symbols.len() as u8;
+        match u8::try_from(item).ok().filter(u8::is_ascii) {
+            Some(b) => self.ascii[b as usize] = Some(index),
+            None => {
+                self.other.insert(item, index);
+            }
+        }
+        self.symbols.push(item);
+        self.priorities.push(priority);
+        Ok(())
+    }
+
+    pub fn index(&self, item: char) -> Option<u8> {
+        if item.is_ascii() {
+            self.ascii[item as usize]
+        } else {
+            self.other.get(&item).copied()
+        }
+    }
+
+    pub fn priority(&self, index: u8) -> u64 {
+        self.priorities[index as usize]
+    }
+
+    pub fn symbol(&self, index: u8) -> char {
+        self.symbols[index as usize]
+    }
+
+    /// Comma separated symbols of every item in the set.
+    pub fn describe(&self, set: ItemSet) -> String {
+        set.indices()
+            .map(|i| self.symbol(i).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
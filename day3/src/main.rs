@@ -1,55 +1,312 @@
-use std::{
-    collections::{hash_map::RandomState, HashSet},
-    io::stdin,
-};
+mod items;
+mod regroup;
 
-fn main() {
-    let mut lines = stdin().lines();
+use std::{env, fmt::Display, fs, process::exit};
 
-    let mut tot: u64 = 0;
-    let mut all: Vec<HashSet<u8>> = Vec::new();
+use items::{ItemSet, PriorityTable};
+use regroup::Goal;
 
-    while let Some(Ok(line)) = lines.next() {
-        let mut set = HashSet::new();
-        let bytes: Vec<u8> = line.bytes().collect();
-        let mut items: Vec<u8> = Vec::new();
+#[derive(Debug)]
+enum Error {
+    UnknownItem {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    Uneven {
+        line: usize,
+        items: usize,
+        compartments: usize,
+    },
+    LeftOver {
+        rucksacks: usize,
+        group_size: usize,
+    },
+    // Description of the offending rucksack or group
+    NotUnique(String),
+}
 
-        for byte in bytes {
-            let item = get_val(byte);
-            set.insert(item);
-            items.push(item);
+/// A rucksack or group where the puzzle expects exactly one common item but finds another count.
+enum Anomaly {
+    Rucksack {
+        line: usize,
+        shared: ItemSet,
+    },
+    Group {
+        lines: (usize, usize),
+        badges: ItemSet,
+    },
+}
+
+impl Anomaly {
+    fn describe(&self, table: &PriorityTable) -> String {
+        let (what, set, noun) = match self {
+            Anomaly::Rucksack { line, shared } => {
+                (format!("line {}", line), *shared, "shared items")
+            }
+            Anomaly::Group { lines, badges } => (
+                format!("group on lines {}-{}", lines.0, lines.1),
+                *badges,
+                "badge candidates",
+            ),
+        };
+        match set.len() {
+            0 => format!("{}: no {}", what, noun),
+            n => format!("{}: {} {}: {}", what, n, noun, table.describe(set)),
         }
+    }
+}
 
-        let half2 = HashSet::<_, RandomState>::from_iter(items.split_off(items.len() / 2));
-        let half1 = HashSet::<_, RandomState>::from_iter(items);
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Check {
+    // Take the first common item and carry on, like the puzzle answer does
+    Lenient,
+    Report,
+    Strict,
+}
 
-        tot += *half1.intersection(&half2).next().unwrap() as u64;
-        all.push(set);
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownItem { line, column, item } => write!(
+                f,
+                "line {}, column {}: item '{}' is not in the priority table",
+                line, column, item
+            ),
+            Error::Uneven {
+                line,
+                items,
+                compartments,
+            } => write!(
+                f,
+                "line {}: {} items can't be split into {} compartments",
+                line, items, compartments
+            ),
+            Error::LeftOver {
+                rucksacks,
+                group_size,
+            } => write!(
+                f,
+                "{} rucksacks left over that don't make up a group of {}",
+                rucksacks, group_size
+            ),
+            Error::NotUnique(description) => {
+                write!(f, "{}, expected exactly one", description)
+            }
+        }
     }
+}
 
-    let mut grp_tot: u64 = 0;
-    let mut group = all;
+struct Config {
+    table: PriorityTable,
+    compartments: usize,
+    group_size: usize,
+    check: Check,
+}
 
-    while group.len() > 0 {
-        let rest = group.split_off(3);
-        let (i, other) = group.split_at_mut(1);
-        let i = &mut i[0];
-        for j in other {
-            i.retain(|k| j.contains(k));
+struct Solution {
+    tot: u64,
+    grp_tot: u64,
+    anomalies: Vec<Anomaly>,
+}
+
+/// Translates a rucksack into item indices, reusing `items` to avoid an allocation per line.
+fn read_items(
+    table: &PriorityTable,
+    line: &str,
+    n: usize,
+    items: &mut Vec<u8>,
+) -> Result<(), Error> {
+    items.clear();
+    for (column, item) in line.chars().enumerate() {
+        items.push(table.index(item).ok_or(Error::UnknownItem {
+            line: n,
+            column: column + 1,
+            item,
+        })?);
+    }
+    Ok(())
+}
+
+fn solve(config: &Config, lines: impl Iterator<Item = String>) -> Result<Solution, Error> {
+    let table = &config.table;
+    let mut anomalies = Vec::new();
+    let mut group = ItemSet::ALL;
+    let mut in_group = 0;
+    let mut items = Vec::new();
+
+    let mut check = |set: ItemSet, anomaly: &dyn Fn() -> Anomaly| -> Result<u64, Error> {
+        if set.len() != 1 {
+            match config.check {
+                Check::Lenient => (),
+                Check::Report => anomalies.push(anomaly()),
+                Check::Strict => return Err(Error::NotUnique(anomaly().describe(table))),
+            }
+        }
+        Ok(set.indices().next().map_or(0, |i| table.priority(i)))
+    };
+
+    let mut tot = 0;
+    let mut grp_tot = 0;
+    for (n, line) in lines.enumerate() {
+        let line_no = n + 1;
+        read_items(table, &line, line_no, &mut items)?;
+        if items.is_empty() || items.len() % config.compartments != 0 {
+            return Err(Error::Uneven {
+                line: line_no,
+                items: items.len(),
+                compartments: config.compartments,
+            });
         }
-        let val = *i.iter().next().unwrap() as u64;
-        grp_tot += val;
-        group = rest;
+
+        let shared = items
+            .chunks(items.len() / config.compartments)
+            .map(ItemSet::from_items)
+            .fold(ItemSet::ALL, ItemSet::intersect);
+        tot += check(shared, &|| Anomaly::Rucksack {
+            line: line_no,
+            shared,
+        })?;
+
+        group = group.intersect(ItemSet::from_items(&items));
+        in_group += 1;
+        if in_group == config.group_size {
+            grp_tot += check(group, &|| Anomaly::Group {
+                lines: (line_no + 1 - config.group_size, line_no),
+                badges: group,
+            })?;
+            group = ItemSet::ALL;
+            in_group = 0;
+        }
+    }
+    if in_group != 0 {
+        return Err(Error::LeftOver {
+            rucksacks: in_group,
+            group_size: config.group_size,
+        });
     }
+    Ok(Solution {
+        tot,
+        grp_tot,
+        anomalies,
+    })
+}
 
-    println!("{}", tot);
-    println!("{}", grp_tot);
+fn print_regroup(config: &Config, goal: Goal, limit: u64) -> Result<(), Error> {
+    let mut sets = Vec::new();
+    let mut items = Vec::new();
+    for (n, line) in aoc_input::lines()
+        .map(|l| l.expect("Read error"))
+        .enumerate()
+    {
+        read_items(&config.table, &line, n + 1, &mut items)?;
+        sets.push(ItemSet::from_items(&items));
+    }
+
+    let outcome = regroup::regroup(&sets, &config.table, config.group_size, goal, limit);
+    match (outcome.best, outcome.exhaustive) {
+        (Some((total, groups)), exhaustive) => {
+            for group in groups {
+                let lines: Vec<String> =
+                    group.members.iter().map(|m| (m + 1).to_string()).collect();
+                println!(
+                    "lines {:<20} badge {} ({})",
+                    lines.join(","),
+                    config.table.symbol(group.badge),
+                    config.table.priority(group.badge)
+                );
+            }
+            println!("{}", total);
+            if !exhaustive {
+                println!("Search limit reached, the total may not be optimal");
+            }
+        }
+        (None, true) => println!(
+            "No partition into groups of {} with exactly one common item exists",
+            config.group_size
+        ),
+        (None, false) => println!("No partition found within the search limit"),
+    }
+    Ok(())
 }
 
-fn get_val(char: u8) -> u8 {
-    match char {
-        65..=90 => char - 64 + 26,
-        97..=123 => char - 96,
-        _ => panic!(),
+fn main() {
+    let mut table_spec = PriorityTable::DEFAULT.to_string();
+    let mut compartments = 2;
+    let mut group_size = 3;
+    let mut check = Check::Lenient;
+    let mut regroup = None;
+    let mut limit = 100_000;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| -> String {
+            args.next()
+                .unwrap_or_else(|| panic!("{} needs a value", name))
+        };
+        let positive = |s: String| -> usize {
+            s.parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .unwrap_or_else(|| panic!("{} is not a positive number", s))
+        };
+        match arg.as_str() {
+            "compartments" => compartments = positive(value("compartments")),
+            "group" => group_size = positive(value("group")),
+            "table" => table_spec = value("table"),
+            "report" => check = Check::Report,
+            "strict" => check = Check::Strict,
+            "regroup" => {
+                regroup = match value("regroup").as_str() {
+                    "max" => Some(Goal::Max),
+                    "min" => Some(Goal::Min),
+                    s => panic!("regroup needs max or min, got {}", s),
+                }
+            }
+            "limit" => limit = positive(value("limit")) as u64,
+            "table-file" => {
+                let path = value("table-file");
+                let text = fs::read_to_string(&path)
+                    .unwrap_or_else(|e| panic!("Can't read {}: {}", path, e));
+                table_spec = aoc_input::normalise(&text).0;
+            }
+            _ => panic!(
+                "Unknown argument {}, expected compartments N, group N, table SPEC, \
+                 table-file FILE, report, strict, regroup <max|min> or limit N",
+                arg
+            ),
+        }
+    }
+
+    let table = PriorityTable::parse(&table_spec).unwrap_or_else(|e| {
+        eprintln!("Invalid priority table: {}", e);
+        exit(1);
+    });
+    let config = Config {
+        table,
+        compartments,
+        group_size,
+        check,
+    };
+
+    if let Some(goal) = regroup {
+        if let Err(e) = print_regroup(&config, goal, limit) {
+            eprintln!("{}", e);
+            exit(1);
+        }
+        return;
+    }
+
+    match solve(&config, aoc_input::lines().map(|l| l.expect("Read error"))) {
+        Ok(solution) => {
+            println!("{}", solution.tot);
+            println!("{}", solution.grp_tot);
+            for anomaly in &solution.anomalies {
+                println!("{}", anomaly.describe(&config.table));
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
     }
 }
@@ -0,0 +1,262 @@
+use crate::items::{ItemSet, PriorityTable};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Max,
+    Min,
+}
+
+#[derive(Clone)]
+pub struct Group {
+    // Indices into the rucksack list
+    pub members: Vec<usize>,
+    pub badge: u8,
+}
+
+pub struct Outcome {
+    pub best: Option<(u64, Vec<Group>)>,
+    // False if the search stopped at its limit. Otherwise no other partition has a better
+    // total, and `None` means the rucksacks can't be partitioned at all.
+    pub exhaustive: bool,
+}
+
+/// Every set of `group_size` rucksacks that share exactly one item.
+fn valid_groups(sets: &[ItemSet], group_size: usize) -> Vec<Group> {
+    fn extend(
+        sets: &[ItemSet],
+        group_size: usize,
+        members: &mut Vec<usize>,
+        common: ItemSet,
+        found: &mut Vec<Group>,
+    ) {
+        if members.len() == group_size {
+            if common.len() == 1 {
+                found.push(Group {
+                    members: members.clone(),
+                    badge: common.indices().next().unwrap(),
+                });
+            }
+            return;
+        }
+        for next in members.last().map_or(0, |l| l + 1)..sets.len() {
+            let common = common.intersect(sets[next]);
+            // Adding members only shrinks the common items
+            if common.len() == 0 {
+                continue;
+            }
+            members.push(next);
+            extend(sets, group_size, members, common, found);
+            members.pop();
+        }
+    }
+
+    let mut found = Vec::new();
+    extend(sets, group_size, &mut Vec::new(), ItemSet::ALL, &mut found);
+    found
+}
+
+/// Branch and bound over exact covers of the rucksacks by valid groups. The rucksack with the
+/// fewest groups still available is always covered next, which runs into dead ends early
+/// instead of discovering them many levels later. Availability is tracked incrementally since
+/// real inputs have hundreds of thousands of valid groups.
+struct Search<'a> {
+    table: &'a PriorityTable,
+    groups: Vec<Group>,
+    // Groups each rucksack is a member of
+    containing: Vec<Vec<usize>>,
+    goal: Goal,
+    group_size: usize,
+    assigned: Vec<bool>,
+    // Number of assigned rucksacks in each group, a group is available while this is zero
+    blocked: Vec<u32>,
+    // Available groups per rucksack and per rucksack and badge
+    options: Vec<u32>,
+    options_by_badge: Vec<[u32; 64]>,
+    chosen: Vec<usize>,
+    score: u64,
+    best: Option<(u64, Vec<usize>)>,
+    nodes: u64,
+    limit: u64,
+    aborted: bool,
+}
+
+impl Search<'_> {
+    fn value(&self, group: usize) -> u64 {
+        self.table.priority(self.groups[group].badge)
+    }
+
+    fn better(&self, a: u64, b: u64) -> bool {
+        match self.goal {
+            Goal::Max => a > b,
+            Goal::Min => a < b,
+        }
+    }
+
+    fn set_assigned(&mut self, rucksack: usize, assigned: bool) {
+        self.assigned[rucksack] = assigned;
+        for i in 0..self.containing[rucksack].len() {
+            let g = self.containing[rucksack][i];
+            let change = if assigned {
+                self.blocked[g] += 1;
+                self.blocked[g] == 1
+            } else {
+                self.blocked[g] -= 1;
+                self.blocked[g] == 0
+            };
+            if change {
+                let badge = self.groups[g].badge as usize;
+                for m in &self.groups[g].members {
+                    if assigned {
+                        self.options[*m] -= 1;
+                        self.options_by_badge[*m][badge] -= 1;
+                    } else {
+                        self.options[*m] += 1;
+                        self.options_by_badge[*m][badge] += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// An optimistic estimate for the unassigned rucksacks. A group's badge can be no better
+    /// than the worst of its members' best available badges, and pairing members off in sorted
+    /// order makes the sum of those as good as it can be.
+    fn bound(&self) -> u64 {
+        let mut best_badges: Vec<u64> = (0..self.assigned.len())
+            .filter(|r| !self.assigned[*r])
+            .map(|r| {
+                let badges = (0..64u8)
+                    .filter(|b| self.options_by_badge[r][*b as usize] > 0)
+                    .map(|b| self.table.priority(b));
+                match self.goal {
+                    Goal::Max => badges.max().unwrap_or(0),
+                    Goal::Min => badges.min().unwrap_or(0),
+                }
+            })
+            .collect();
+        best_badges.sort_unstable();
+        if self.goal == Goal::Max {
+            best_badges.reverse();
+        }
+        best_badges
+            .iter()
+            .skip(self.group_size - 1)
+            .step_by(self.group_size)
+            .sum()
+    }
+
+    fn place(&mut self) {
+        self.nodes += 1;
+        if self.nodes > self.limit {
+            self.aborted = true;
+            return;
+        }
+
+        let Some(next) = (0..self.assigned.len())
+            .filter(|r| !self.assigned[*r])
+            .min_by_key(|r| self.options[*r])
+        else {
+            if self
+                .best
+                .as_ref()
+                .is_none_or(|(b, _)| self.better(self.score, *b))
+            {
+                self.best = Some((self.score, self.chosen.clone()));
+            }
+            return;
+        };
+        if self.options[next] == 0 {
+            return;
+        }
+        if let Some((best, _)) = self.best {
+            if !self.better(self.score + self.bound(), best) {
+                return;
+            }
+        }
+
+        let mut options: Vec<usize> = self.containing[next]
+            .iter()
+            .copied()
+            .filter(|g| self.blocked[*g] == 0)
+            .collect();
+        options.sort_by_key(|g| self.value(*g));
+        if self.goal == Goal::Max {
+            options.reverse();
+        }
+        for g in options {
+            for i in 0..self.group_size {
+                self.set_assigned(self.groups[g].members[i], true);
+            }
+            self.score += self.value(g);
+            self.chosen.push(g);
+
+            self.place();
+
+            self.chosen.pop();
+            self.score -= self.value(g);
+            for i in 0..self.group_size {
+                self.set_assigned(self.groups[g].members[i], false);
+            }
+            if self.aborted {
+                return;
+            }
+        }
+    }
+}
+
+/// Partitions the rucksacks into groups of `group_size` with exactly one common item each,
+/// looking for the best total badge priority. Gives up after `limit` search nodes, which must be
+/// at least one.
+pub fn regroup(
+    sets: &[ItemSet],
+    table: &PriorityTable,
+    group_size: usize,
+    goal: Goal,
+    limit: u64,
+) -> Outcome {
+    assert!(limit > 0, "The search limit must be at least 1");
+    if !sets.len().is_multiple_of(group_size) {
+        return Outcome {
+            best: None,
+            exhaustive: true,
+        };
+    }
+
+    let groups = valid_groups(sets, group_size);
+    let mut containing = vec![Vec::new(); sets.len()];
+    let mut options = vec![0; sets.len()];
+    let mut options_by_badge = vec![[0; 64]; sets.len()];
+    for (g, group) in groups.iter().enumerate() {
+        for m in &group.members {
+            containing[*m].push(g);
+            options[*m] += 1;
+            options_by_badge[*m][group.badge as usize] += 1;
+        }
+    }
+
+    let mut search = Search {
+        table,
+        containing,
+        goal,
+        group_size,
+        assigned: vec![false; sets.len()],
+        blocked: vec![0; groups.len()],
+        groups,
+        options,
+        options_by_badge,
+        chosen: Vec::new(),
+        score: 0,
+        best: None,
+        nodes: 0,
+        limit,
+        aborted: false,
+    };
+    search.place();
+    Outcome {
+        best: search.best.map(|(score, chosen)| {
+            let groups = chosen.iter().map(|g| search.groups[*g].clone()).collect();
+            (score, groups)
+        }),
+        exhaustive: !search.aborted,
+    }
+}
@@ -0,0 +1,99 @@
+use crate::interval::{Interval, IntervalSet};
+
+pub struct Assignment {
+    pub line: usize,
+    // Position of the elf on its line, starting at 1
+    pub elf: usize,
+    pub sections: Interval,
+}
+
+/// Sweeps over all assignments and returns every stretch of sections with the number of elves
+/// covering it. Stretches with no coverage are left out.
+pub fn depth_profile(assignments: &[Assignment]) -> Vec<(Interval, u32)> {
+    // Ends are stored one past the range so the events stay in u64 without overflowing
+    let mut events: Vec<(u64, i32)> = assignments
+        .iter()
+        .flat_map(|a| {
+            [
+                (a.sections.start as u64, 1),
+                (a.sections.end as u64 + 1, -1),
+            ]
+        })
+        .collect();
+    events.sort_unstable();
+
+    let mut profile = Vec::new();
+    let mut depth = 0;
+    for (i, (pos, delta)) in events.iter().enumerate() {
+        depth += delta;
+        if let Some((next, _)) = events.get(i + 1) {
+            if *next > *pos && depth > 0 {
+                profile.push((
+                    Interval {
+                        start: *pos as u32,
+                        end: (*next - 1) as u32,
+                    },
+                    depth as u32,
+                ));
+            }
+        }
+    }
+    profile
+}
+
+/// The highest coverage and every section that reaches it.
+pub fn most_covered(profile: &[(Interval, u32)]) -> (u32, IntervalSet) {
+    let max = profile.iter().map(|(_, d)| *d).max().unwrap_or(0);
+    let sections = profile
+        .iter()
+        .filter(|(_, d)| *d == max)
+        .map(|(i, _)| *i)
+        .collect();
+    (max, sections)
+}
+
+/// Sections between the lowest and highest assigned ones that nobody covers.
+pub fn gaps(covered: &IntervalSet) -> IntervalSet {
+    let (Some(first), Some(last)) = (covered.iter().next(), covered.iter().last()) else {
+        return IntervalSet::default();
+    };
+    let span: IntervalSet = [Interval {
+        start: first.start,
+        end: last.end,
+    }]
+    .into_iter()
+    .collect();
+    span.difference(covered)
+}
+
+/// Indices of the fewest assignments that still cover every covered section. Within each
+/// stretch of coverage, repeatedly taking the assignment that reaches furthest among those
+/// starting at or before the first uncovered section is optimal.
+pub fn minimum_cover(assignments: &[Assignment], covered: &IntervalSet) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..assignments.len()).collect();
+    order.sort_unstable_by_key(|i| assignments[*i].sections.start);
+
+    let mut chosen = Vec::new();
+    let mut next = 0;
+    for stretch in covered.iter() {
+        let mut from = stretch.start as u64;
+        while from <= stretch.end as u64 {
+            let mut best: Option<usize> = None;
+            while next < order.len() && assignments[order[next]].sections.start as u64 <= from {
+                let candidate = order[next];
+                if best.is_none_or(|b| {
+                    assignments[candidate].sections.end > assignments[b].sections.end
+                }) {
+                    best = Some(candidate);
+                }
+                next += 1;
+            }
+            // Anything passed over in an earlier step ends before `from`, otherwise it would have
+            // been taken then
+            let best = best.expect("covered sections always have an assignment starting there");
+            chosen.push(best);
+            from = assignments[best].sections.end as u64 + 1;
+        }
+    }
+    chosen
+}
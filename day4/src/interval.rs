@@ -0,0 +1,167 @@
+use std::{fmt::Display, str::FromStr};
+
+/// An inclusive range of section IDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Interval {
+    pub fn new(start: u32, end: u32) -> Option<Interval> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn len(&self) -> u64 {
+        (self.end - self.start) as u64 + 1
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && self.end >= other.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && self.end >= other.start
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    pub fn union(&self, other: &Interval) -> IntervalSet {
+        IntervalSet::from_iter([*self, *other])
+    }
+
+    pub fn difference(&self, other: &Interval) -> IntervalSet {
+        IntervalSet::from_iter([*self]).difference(&IntervalSet::from_iter([*other]))
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("'{}' is not a range like 2-4", s))?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("'{}' is not a section ID", n))
+        };
+        Interval::new(parse(start)?, parse(end)?)
+            .ok_or_else(|| format!("range '{}' ends before it starts", s))
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of section IDs kept as sorted, disjoint and non-adjacent intervals.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        let mut sorted: Vec<Interval> = iter.into_iter().collect();
+        sorted.sort_unstable();
+
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for i in sorted {
+            match intervals.last_mut() {
+                // Merge overlapping and touching intervals
+                Some(last) if i.start as u64 <= last.end as u64 + 1 => {
+                    last.end = last.end.max(i.end)
+                }
+                _ => intervals.push(i),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl IntervalSet {
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// Whether every section of `interval` is in the set. Intervals in the set never touch, so
+    /// one of them has to hold all of it.
+    pub fn contains(&self, interval: &Interval) -> bool {
+        let after = self
+            .intervals
+            .partition_point(|i| i.start <= interval.start);
+        after > 0 && self.intervals[after - 1].contains(interval)
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.iter().all(|i| self.contains(i))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(both) = a.intersection(&b) {
+                out.push(both);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals: out }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = Vec::new();
+        let mut j = 0;
+        for a in &self.intervals {
+            let mut start = a.start as u64;
+            while j < other.intervals.len() && other.intervals[j].end < a.start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start <= a.end {
+                let b = other.intervals[k];
+                if b.start as u64 > start {
+                    out.push(Interval {
+                        start: start as u32,
+                        end: b.start - 1,
+                    });
+                }
+                start = start.max(b.end as u64 + 1);
+                k += 1;
+            }
+            if start <= a.end as u64 {
+                out.push(Interval {
+                    start: start as u32,
+                    end: a.end,
+                });
+            }
+        }
+        IntervalSet { intervals: out }
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.iter().map(Interval::to_string).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
@@ -1,30 +1,141 @@
-use std::io::stdin;
+mod coverage;
+mod interval;
+
+use std::env;
+
+use coverage::Assignment;
+use interval::{Interval, IntervalSet};
 
 fn main() {
-    let mut lines = stdin().lines();
+    let mut report = false;
+    let mut coverage = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "report" => report = true,
+            "coverage" => coverage = true,
+            _ => panic!("Unknown argument {}, expected report or coverage", arg),
+        }
+    }
+
+    let mut lines = aoc_input::lines();
     let mut any_overlap: u32 = 0;
     let mut complete_overlap: u32 = 0;
+    let mut n = 0;
+    let mut assignments = Vec::new();
 
     while let Some(Ok(line)) = lines.next() {
-        let sections_str: Vec<_> = line.split(',').collect();
-        let sec1 = parse_section(sections_str[0]);
-        let sec2 = parse_section(sections_str[1]);
+        n += 1;
+        let sections = parse_sections(&line).unwrap_or_else(|e| panic!("Line {}: {}", n, e));
+        if coverage {
+            assignments.extend(sections.iter().enumerate().map(|(i, s)| Assignment {
+                line: n,
+                elf: i + 1,
+                sections: *s,
+            }));
+        }
+
+        let mut relations = Vec::new();
+        let mut overlapping = false;
+        let mut containing = false;
+        for (i, a) in sections.iter().enumerate() {
+            for (j, b) in sections.iter().enumerate().skip(i + 1) {
+                let relation = if a == b {
+                    format!("{} equals {}", i + 1, j + 1)
+                } else if a.contains(b) || b.contains(a) {
+                    let (outer, inner) = if a.contains(b) { (i, j) } else { (j, i) };
+                    let extra = sections[outer].difference(&sections[inner]);
+                    format!(
+                        "{} contains {} (+{} sections: {})",
+                        outer + 1,
+                        inner + 1,
+                        extra.len(),
+                        extra
+                    )
+                } else if a.overlaps(b) {
+                    let shared = a.intersection(b).unwrap();
+                    format!(
+                        "{} overlaps {} at {}, together {}",
+                        i + 1,
+                        j + 1,
+                        shared,
+                        a.union(b)
+                    )
+                } else {
+                    continue;
+                };
+                overlapping = true;
+                containing |= a.contains(b) || b.contains(a);
+                relations.push(relation);
+            }
+        }
 
-        if sec1.0 <= sec2.1 && sec1.1 >= sec2.0 {
+        if overlapping {
             any_overlap += 1;
         }
-        if (sec1.1 <= sec2.1 && sec1.0 >= sec2.0) || (sec2.1 <= sec1.1 && sec2.0 >= sec1.0) {
+        if containing {
             complete_overlap += 1;
         }
+        if report {
+            let sets: Vec<IntervalSet> = sections
+                .iter()
+                .map(|s| [*s].into_iter().collect())
+                .collect();
+            let union = sets.iter().fold(IntervalSet::default(), |u, s| u.union(s));
+            let common = sets[1..]
+                .iter()
+                .fold(sets[0].clone(), |c, s| c.intersection(s));
+            println!(
+                "line {}: {} covers {} ({} sections), common to all: {}{}{}",
+                n,
+                line,
+                union,
+                union.len(),
+                if common.len() == 0 {
+                    "none".to_string()
+                } else {
+                    common.to_string()
+                },
+                if relations.is_empty() { "" } else { ", " },
+                relations.join(", ")
+            );
+        }
     }
     println!("{}", complete_overlap);
     println!("{}", any_overlap);
+
+    if coverage {
+        print_coverage(&assignments);
+    }
+}
+
+fn print_coverage(assignments: &[Assignment]) {
+    let profile = coverage::depth_profile(assignments);
+    let covered: IntervalSet = profile.iter().map(|(i, _)| *i).collect();
+    println!("Covered: {} ({} sections)", covered, covered.len());
+
+    let (depth, busiest) = coverage::most_covered(&profile);
+    println!("Most covered: {} by {} elves", busiest, depth);
+
+    let gaps = coverage::gaps(&covered);
+    if gaps.len() == 0 {
+        println!("Gaps: none");
+    } else {
+        println!("Gaps: {} ({} sections)", gaps, gaps.len());
+    }
+
+    let cover = coverage::minimum_cover(assignments, &covered);
+    let cover_set: IntervalSet = cover.iter().map(|i| assignments[*i].sections).collect();
+    assert!(
+        cover_set.is_superset(&covered),
+        "The covering set misses sections"
+    );
+    println!("Smallest covering set: {} elves", cover.len());
+    for i in cover {
+        let a = &assignments[i];
+        println!("  line {} elf {}: {}", a.line, a.elf, a.sections);
+    }
 }
 
-fn parse_section(section_str: &str) -> (u32, u32) {
-    let idxs: Vec<_> = section_str.split('-').collect();
-    (
-        idxs[0].parse::<u32>().unwrap(),
-        idxs[1].parse::<u32>().unwrap(),
-    )
+fn parse_sections(line: &str) -> Result<Vec<Interval>, String> {
+    line.split(',').map(str::parse).collect()
 }
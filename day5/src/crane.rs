@@ -0,0 +1,152 @@
+/// Part of a move that the crane carries at once. A lift takes `size` crates off the top of the
+/// source stack and sets them down on the target stack, upside down if `reversed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lift {
+    pub size: usize,
+    pub reversed: bool,
+}
+
+/// How a crane carries out a move. `step` is the number of moves made before this one.
+pub trait Crane {
+    fn name(&self) -> String;
+
+    fn lifts(&self, step: usize, count: usize) -> Vec<Lift>;
+
+    /// Number of moves after which the crane behaves the same way again.
+    fn period(&self) -> usize {
+        1
+    }
+
+    /// Every lift costs one unit unless the crane says otherwise.
+    fn cost(&self, lifts: &[Lift]) -> u64 {
+        lifts.len() as u64
+    }
+}
+
+/// Moves one crate at a time, which reverses the crates moved.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    // Carrying the crates one by one ends the same as turning them all over at once
+    fn lifts(&self, _: usize, count: usize) -> Vec<Lift> {
+        vec![Lift {
+            size: count,
+            reversed: true,
+        }]
+    }
+
+    fn cost(&self, lifts: &[Lift]) -> u64 {
+        lifts.iter().map(|l| l.size as u64).sum()
+    }
+}
+
+/// Moves all crates at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn lifts(&self, _: usize, count: usize) -> Vec<Lift> {
+        vec![Lift {
+            size: count,
+            reversed: false,
+        }]
+    }
+}
+
+/// Keeps the crates' order but can lift only so many at a time, so a larger move is split into
+/// full lifts from the top followed by the remainder.
+pub struct Capacity(pub usize);
+
+impl Crane for Capacity {
+    fn name(&self) -> String {
+        format!("capacity {}", self.0)
+    }
+
+    fn lifts(&self, _: usize, count: usize) -> Vec<Lift> {
+        (0..count)
+            .step_by(self.0)
+            .map(|start| Lift {
+                size: self.0.min(count - start),
+                reversed: false,
+            })
+            .collect()
+    }
+}
+
+/// Moves all crates at once but turns them over on every other move, starting with the first.
+pub struct Alternating;
+
+impl Crane for Alternating {
+    fn name(&self) -> String {
+        "alternating".to_string()
+    }
+
+    fn lifts(&self, step: usize, count: usize) -> Vec<Lift> {
+        vec![Lift {
+            size: count,
+            reversed: step.is_multiple_of(2),
+        }]
+    }
+
+    fn period(&self) -> usize {
+        2
+    }
+}
+
+/// Another crane with a fixed charge on top of every move.
+pub struct Metered {
+    pub crane: Box<dyn Crane>,
+    pub per_move: u64,
+}
+
+impl Crane for Metered {
+    fn name(&self) -> String {
+        format!("{} at {} per move", self.crane.name(), self.per_move)
+    }
+
+    fn lifts(&self, step: usize, count: usize) -> Vec<Lift> {
+        self.crane.lifts(step, count)
+    }
+
+    fn period(&self) -> usize {
+        self.crane.period()
+    }
+
+    fn cost(&self, lifts: &[Lift]) -> u64 {
+        self.per_move + self.crane.cost(lifts)
+    }
+}
+
+/// Parses a crane such as `9000`, `9001`, `capacity=3` or `alternating`. A `@N` suffix adds a
+/// charge of N per move.
+pub fn parse(spec: &str) -> Result<Box<dyn Crane>, String> {
+    if let Some((crane, per_move)) = spec.rsplit_once('@') {
+        let per_move = per_move
+            .parse()
+            .map_err(|_| format!("'{}' is not a cost per move", per_move))?;
+        return Ok(Box::new(Metered {
+            crane: parse(crane)?,
+            per_move,
+        }));
+    }
+    match spec {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "alternating" => Ok(Box::new(Alternating)),
+        _ => match spec.strip_prefix("capacity=").map(str::parse) {
+            Some(Ok(n)) if n > 0 => Ok(Box::new(Capacity(n))),
+            Some(_) => Err(format!("'{}' needs a capacity of at least 1", spec)),
+            None => Err(format!(
+                "unknown crane '{}', expected 9000, 9001, capacity=N or alternating",
+                spec
+            )),
+        },
+    }
+}
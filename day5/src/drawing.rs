@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+
+/// Every stack's crates from the bottom up, so that moving crates between the tops of two
+/// stacks is a single transfer between the ends of two vectors.
+pub type Stacks = Vec<Vec<String>>;
+
+/// Stacks with every crate replaced by its index in the list of distinct labels, so that moving
+/// a crate copies four bytes however long its label is.
+#[derive(Clone)]
+pub struct Compact {
+    pub stacks: Vec<Vec<u32>>,
+    pub labels: Vec<String>,
+}
+
+impl Compact {
+    pub fn new(stacks: &Stacks) -> Compact {
+        let mut labels: Vec<&str> = stacks
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        labels.sort_unstable();
+        let index: HashMap<&str, u32> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (*l, i as u32))
+            .collect();
+        Compact {
+            stacks: stacks
+                .iter()
+                .map(|s| s.iter().map(|l| index[l.as_str()]).collect())
+                .collect(),
+            labels: labels.into_iter().map(String::from).collect(),
+        }
+    }
+
+    pub fn expand(&self) -> Stacks {
+        self.stacks
+            .iter()
+            .map(|s| s.iter().map(|l| self.labels[*l as usize].clone()).collect())
+            .collect()
+    }
+
+    /// The labels of the top crates, skipping empty stacks.
+    pub fn tops(&self) -> String {
+        self.tops_of(&self.stacks)
+    }
+
+    /// Same as `tops` for other stacks made of the same labels.
+    pub fn tops_of(&self, stacks: &[Vec<u32>]) -> String {
+        stacks
+            .iter()
+            .filter_map(|s| s.last())
+            .map(|l| self.labels[*l as usize].as_str())
+            .collect()
+    }
+}
+
+/// Reads a crate drawing from its crate rows and its label row. Every crate is a `[label]` cell
+/// and all cells share the width of the widest one, separated by single spaces, so the column of
+/// a crate follows from where its cell starts. The label row has to be laid out the way `render`
+/// draws it, each stack number centred in its cell and rounded to the left, so that rendering
+/// gives the drawing back.
+pub fn parse(rows: &[String], labels: &str) -> Result<Stacks, String> {
+    let num_stacks =
+        parse_labels(labels).ok_or_else(|| format!("'{}' is not a label row", labels))?;
+    let cells: Vec<Vec<(usize, String)>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            crates_in_row(row).ok_or_else(|| format!("line {}: unclosed crate in '{}'", i + 1, row))
+        })
+        .collect::<Result<_, _>>()?;
+    let width = cell_width(
+        cells.iter().flatten().map(|(_, label)| label.as_str()),
+        num_stacks,
+    );
+
+    let label_line = rows.len() + 1;
+    for (stack, (start, number)) in words(labels).into_iter().enumerate() {
+        let expected = stack * (width + 1) + (width - number.len()) / 2;
+        if start != expected {
+            return Err(format!(
+                "line {}: stack number {} at column {} should be at column {}",
+                label_line,
+                number,
+                start + 1,
+                expected + 1
+            ));
+        }
+    }
+
+    let mut stacks: Stacks = vec![Vec::new(); num_stacks];
+    for (i, row) in cells.iter().enumerate() {
+        for (start, label) in row {
+            if start % (width + 1) != 0 {
+                return Err(format!(
+                    "line {}: crate [{}] at column {} isn't aligned to a stack",
+                    i + 1,
+                    label,
+                    start + 1
+                ));
+            }
+            let stack = start / (width + 1);
+            if stack >= num_stacks {
+                return Err(format!(
+                    "line {}: crate [{}] is right of the last stack",
+                    i + 1,
+                    label
+                ));
+            }
+            stacks[stack].push(label.clone());
+        }
+    }
+    // The rows were read from the top down
+    for stack in &mut stacks {
+        stack.reverse();
+    }
+    Ok(stacks)
+}
+
+// Column and label of every `[label]` cell in a row
+fn crates_in_row(row: &str) -> Option<Vec<(usize, String)>> {
+    let chars: Vec<char> = row.chars().collect();
+    let mut crates = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            let len = chars[i + 1..].iter().position(|c| *c == ']')?;
+            crates.push((i, chars[i + 1..i + 1 + len].iter().collect()));
+            i += len + 2;
+        } else {
+            i += 1;
+        }
+    }
+    Some(crates)
+}
+
+// Column and text of every word in a row
+fn words(row: &str) -> Vec<(usize, String)> {
+    let mut words: Vec<(usize, String)> = Vec::new();
+    let mut in_word = false;
+    for (i, c) in row.chars().enumerate() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if in_word {
+            words.last_mut().unwrap().1.push(c);
+        } else {
+            words.push((i, c.to_string()));
+            in_word = true;
+        }
+    }
+    words
+}
+
+// Every cell is as wide as the widest crate, and wide enough for the stack numbers
+fn cell_width<'a>(labels: impl Iterator<Item = &'a str>, num_stacks: usize) -> usize {
+    labels
+        .map(|label| label.chars().count() + 2)
+        .max()
+        .unwrap_or(3)
+        .max(num_stacks.to_string().len())
+}
+
+/// Number of stacks named by a label row such as ` 1   2   3 `, or `None` if the row isn't one.
+pub fn parse_labels(row: &str) -> Option<usize> {
+    let mut count = 0;
+    for (i, label) in row.split_whitespace().enumerate() {
+        if label.parse::<usize>().ok()? != i + 1 {
+            return None;
+        }
+        count += 1;
+    }
+    (count > 0).then_some(count)
+}
+
+/// Draws the stacks in the puzzle's format, label row included. Every line is padded to the
+/// full width just like the puzzle input.
+pub fn render(stacks: &Stacks) -> String {
+    let width = cell_width(stacks.iter().flatten().map(String::as_str), stacks.len());
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for row in 0..height {
+        let cells: Vec<String> = stacks
+            .iter()
+            .map(|stack| {
+                // The bottom crate of every stack is on the last row
+                match height.checked_sub(row + 1).filter(|i| *i < stack.len()) {
+                    Some(i) => format!("{:<width$}", format!("[{}]", stack[i])),
+                    None => " ".repeat(width),
+                }
+            })
+            .collect();
+        out.push_str(&cells.join(" "));
+        out.push('\n');
+    }
+
+    let labels: Vec<String> = (1..=stacks.len())
+        .map(|n| {
+            let n = n.to_string();
+            let pad = (width - n.len()) / 2;
+            format!("{:<width$}", format!("{}{}", " ".repeat(pad), n))
+        })
+        .collect();
+    out.push_str(&labels.join(" "));
+    out.push('\n');
+    out
+}
@@ -1,80 +1,173 @@
-use std::{
-    collections::VecDeque,
-    io::{stdin, Lines, StdinLock},
-};
+mod crane;
+mod drawing;
+mod planner;
+mod program;
+
+use std::{env, process};
+
+use crane::{Crane, CrateMover9000, CrateMover9001};
+use drawing::Stacks;
+use planner::Plan;
+use program::{Program, Session};
 
 fn main() {
-    let mut lines = stdin().lines();
-    let mut stacks = construct_stacks(&mut lines);
-    let mut stacks2 = stacks.clone();
+    let mut render = false;
+    let mut render_final = false;
+    let mut trace = false;
+    let mut at: Option<usize> = None;
+    let mut crane: Box<dyn Crane> = Box::new(CrateMover9000);
+    let mut cranes: Vec<Box<dyn Crane>> = Vec::new();
+    let mut target: Option<String> = None;
+    let mut limit = 1_000_000;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "render" => render = true,
+            "final" => render_final = true,
+            "trace" => trace = true,
+            "plan" => target = Some(args.next().expect("plan needs a top string")),
+            "limit" => {
+                limit = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("limit needs a number")
+            }
+            "at" => {
+                at = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("at needs a number of moves"),
+                )
+            }
+            "crane" => {
+                let spec = args.next().expect("crane needs a crane model");
+                crane = crane::parse(&spec).unwrap_or_else(|e| fail(&e));
+            }
+            "cranes" => {
+                let specs = args.next().expect("cranes needs a list of crane models");
+                cranes = specs
+                    .split(',')
+                    .map(crane::parse)
+                    .collect::<Result<_, _>>()
+                    .unwrap_or_else(|e| fail(&e));
+            }
+            _ => panic!(
+                "Unknown argument {}, expected render, final, trace, at N, plan TOPS, limit N, crane SPEC or cranes SPEC,..",
+                arg
+            ),
+        }
+    }
 
-    // Skip empty line
-    lines.next();
-    let collected_lines: Vec<String> = lines.into_iter().map(|l| l.unwrap()).collect();
+    // The drawing pads its rows to the full width
+    let mut lines = aoc_input::lines().keep_trailing_spaces();
+    let input = read_input(&mut lines, !render && target.is_none());
+    // Warns about the input before an error ends the program
+    drop(lines);
+    let (stacks, program) = input.unwrap_or_else(|e| fail(&e));
+    if render {
+        print!("{}", drawing::render(&stacks));
+        return;
+    }
+    if let Some(target) = target {
+        print_plan(&stacks, crane.as_ref(), &target, limit);
+        return;
+    }
+    let program = program.expect("The moves are read unless rendering or planning");
+
+    if trace || at.is_some() {
+        let mut session = Session::new(&program, stacks, crane.as_ref());
+        if trace {
+            print!("{}", drawing::render(&session.stacks()));
+            while let Some((line, m)) = session.redo() {
+                println!("\n{}: {} (line {})", session.done(), m, line);
+                print!("{}", drawing::render(&session.stacks()));
+            }
+        }
+        if let Some(target) = at {
+            session.seek(target);
+            println!("After move {}:", session.done());
+            print!("{}", drawing::render(&session.stacks()));
+        }
+        return;
+    }
+
+    if !cranes.is_empty() {
+        for crane in &cranes {
+            let mut session = Session::new(&program, stacks.clone(), crane.as_ref());
+            session.seek(usize::MAX);
+            println!(
+                "{}: {} (cost {})",
+                crane.name(),
+                session.tops(),
+                session.cost()
+            );
+        }
+        return;
+    }
 
-    read_and_exec_ops(collected_lines.clone(), &mut stacks, false);
-    read_and_exec_ops(collected_lines, &mut stacks2, true);
+    let mut single = Session::new(&program, stacks.clone(), &CrateMover9000);
+    let mut multiple = Session::new(&program, stacks, &CrateMover9001);
+    single.seek(usize::MAX);
+    multiple.seek(usize::MAX);
 
-    println!("{}", get_top_string(&stacks));
-    println!("{}", get_top_string(&stacks2));
+    println!("{}", single.tops());
+    println!("{}", multiple.tops());
+    if render_final {
+        println!();
+        print!("{}", drawing::render(&single.stacks()));
+        println!();
+        print!("{}", drawing::render(&multiple.stacks()));
+    }
 }
 
-fn construct_stacks(lines: &mut Lines<StdinLock>) -> Vec<VecDeque<char>> {
-    let mut stacks: Vec<VecDeque<char>> = Vec::new();
-
-    'outer: while let Some(Ok(line)) = lines.next() {
-        let mut chars = line.chars();
-        let mut pos = chars.nth(1);
-        let mut i: usize = 0;
-        while let Some(c) = pos {
-            if stacks.len() as i32 - 1 < i as i32 {
-                stacks.push(VecDeque::new());
-            }
-            if c.is_alphabetic() {
-                stacks[i].push_back(c);
-            } else if c.is_ascii_digit() {
-                // We've reached the stack lables
-                break 'outer;
+// Prints a complete puzzle input, so the plan can be checked by running it
+fn print_plan(stacks: &Stacks, crane: &dyn Crane, target: &str, limit: usize) {
+    match planner::plan(stacks, crane, target, limit) {
+        Plan::Found(moves) => {
+            print!("{}", drawing::render(stacks));
+            println!();
+            for m in moves {
+                println!("{}", m);
             }
-            pos = chars.nth(3);
-            i += 1;
         }
+        Plan::Impossible => fail(&format!("No sequence of moves ends with tops {}", target)),
+        Plan::GaveUp => fail(&format!(
+            "No plan for tops {} found within {} arrangements",
+            target, limit
+        )),
     }
-    stacks
 }
 
-fn read_and_exec_ops(lines: Vec<String>, stacks: &mut Vec<VecDeque<char>>, multi: bool) {
-    let mut line_it = lines.into_iter();
-    while let Some(line) = line_it.next() {
-        let mut split = line.split_ascii_whitespace();
-        let num = split.nth(1).unwrap().parse().unwrap();
-        let from = split.nth(1).unwrap().parse::<usize>().unwrap() - 1;
-        let to = split.nth(1).unwrap().parse::<usize>().unwrap() - 1;
+fn fail(error: &str) -> ! {
+    eprintln!("{}", error);
+    process::exit(1);
+}
 
-        if !multi {
-            for _ in 0..num {
-                let item = stacks[from].pop_front().unwrap();
-                stacks[to].push_front(item);
-            }
-        } else {
-            let mut tmp_queue = VecDeque::new();
-            for _ in 0..num {
-                tmp_queue.push_back(stacks[from].pop_front().unwrap());
-            }
-            for _ in 0..num {
-                stacks[to].push_front(tmp_queue.pop_back().unwrap());
-            }
-        }
+// Reads the drawing and, if `with_program`, the validated moves after it
+fn read_input(
+    lines: &mut aoc_input::Lines,
+    with_program: bool,
+) -> Result<(Stacks, Option<Program>), String> {
+    let (stacks, drawing_lines) = construct_stacks(lines)?;
+    if !with_program {
+        return Ok((stacks, None));
     }
+    // Skip empty line
+    lines.next();
+    let program = Program::parse(lines.map(|l| l.unwrap()), drawing_lines + 2)?;
+    program.validate(&stacks)?;
+    Ok((stacks, Some(program)))
 }
 
-fn get_top_string(stacks: &Vec<VecDeque<char>>) -> String {
-    let mut output = String::new();
-    let mut it = stacks.iter();
-    while let Some(stack) = it.next() {
-        if let Some(c) = stack.front() {
-            output.push(*c);
+// Also returns the number of lines the drawing took up, label row included
+fn construct_stacks(lines: &mut aoc_input::Lines) -> Result<(Stacks, usize), String> {
+    let mut rows = Vec::new();
+    while let Some(Ok(line)) = lines.next() {
+        if drawing::parse_labels(&line).is_some() {
+            // We've reached the stack labels
+            return Ok((drawing::parse(&rows, &line)?, rows.len() + 1));
         }
+        rows.push(line);
     }
-    output
+    Err("The drawing has no row of stack labels".to_string())
 }
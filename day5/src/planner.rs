@@ -0,0 +1,160 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use crate::{
+    crane::Crane,
+    drawing::{Compact, Stacks},
+    program,
+    program::Move,
+};
+
+pub enum Plan {
+    Found(Vec<Move>),
+    // Every reachable arrangement was tried
+    Impossible,
+    GaveUp,
+}
+
+struct Node {
+    stacks: Vec<Vec<u32>>,
+    // The arrangement this one was reached from and the move that did it
+    parent: Option<(usize, Move)>,
+    depth: usize,
+}
+
+struct Search {
+    // The target's label for every stack, when it can be split up like that
+    target_tops: Option<Vec<u32>>,
+}
+
+impl Search {
+    /// A lower bound on the moves still needed. A move changes the top crate of two stacks at
+    /// most, so half the stacks with the wrong top crate is never too many.
+    fn estimate(&self, stacks: &[Vec<u32>]) -> usize {
+        let Some(target_tops) = &self.target_tops else {
+            return 0;
+        };
+        let wrong = stacks
+            .iter()
+            .zip(target_tops)
+            .filter(|(s, t)| s.last() != Some(t))
+            .count();
+        wrong.div_ceil(2)
+    }
+}
+
+/// A* search for the fewest moves after which the top crates spell `target`. Gives up once
+/// `limit` arrangements have been seen.
+pub fn plan(stacks: &Stacks, crane: &dyn Crane, target: &str, limit: usize) -> Plan {
+    let compact = Compact::new(stacks);
+    let start = compact.stacks.clone();
+
+    let mut counts = vec![0; compact.labels.len()];
+    for label in compact.stacks.iter().flatten() {
+        counts[*label as usize] += 1;
+    }
+    if !spellable(target, &compact.labels, &mut counts, stacks.len()) {
+        return Plan::Impossible;
+    }
+
+    // With single character labels and a character for every stack, each stack's top crate
+    // is known. Otherwise some stacks may end up empty and there's no telling which.
+    let target_tops = if compact.labels.iter().all(|l| l.chars().count() == 1)
+        && target.chars().count() == stacks.len()
+    {
+        target
+            .chars()
+            .map(|c| {
+                compact
+                    .labels
+                    .iter()
+                    .position(|l| l.starts_with(c))
+                    .map(|i| i as u32)
+            })
+            .collect()
+    } else {
+        None
+    };
+    let search = Search { target_tops };
+
+    // A crane that changes behaviour between moves can reach different arrangements from the
+    // same stacks depending on where it is in its cycle
+    let period = crane.period();
+    // Arrangements in the order they were found
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut seen: HashSet<(Vec<Vec<u32>>, usize)> = HashSet::from([(start.clone(), 0)]);
+    // Ordered by estimated plan length, then by most moves made to finish deeper paths first
+    let mut queue = BinaryHeap::from([Reverse((search.estimate(&start), Reverse(0), 0))]);
+    nodes.push(Node {
+        stacks: start,
+        parent: None,
+        depth: 0,
+    });
+
+    while let Some(Reverse((_, _, node))) = queue.pop() {
+        if compact.tops_of(&nodes[node].stacks) == target {
+            return Plan::Found(moves_to(&nodes, node));
+        }
+        let depth = nodes[node].depth;
+        for from in 0..nodes[node].stacks.len() {
+            for to in (0..nodes[node].stacks.len()).filter(|to| *to != from) {
+                for count in 1..=nodes[node].stacks[from].len() {
+                    let m = Move {
+                        count,
+                        from: from + 1,
+                        to: to + 1,
+                    };
+                    let mut stacks = nodes[node].stacks.clone();
+                    program::apply(&mut stacks, crane, depth, &m);
+                    if !seen.insert((stacks.clone(), (depth + 1) % period)) {
+                        continue;
+                    }
+                    if seen.len() > limit {
+                        return Plan::GaveUp;
+                    }
+                    let estimate = depth + 1 + search.estimate(&stacks);
+                    nodes.push(Node {
+                        stacks,
+                        parent: Some((node, m)),
+                        depth: depth + 1,
+                    });
+                    queue.push(Reverse((estimate, Reverse(depth + 1), nodes.len() - 1)));
+                }
+            }
+        }
+    }
+    Plan::Impossible
+}
+
+// Whether `target` can be spelled by the top crates of at most `stacks` stacks, using no label
+// more often than `counts` has crates with it
+fn spellable(target: &str, labels: &[String], counts: &mut [usize], stacks: usize) -> bool {
+    if target.is_empty() {
+        return true;
+    }
+    if stacks == 0 {
+        return false;
+    }
+    (0..labels.len()).any(|l| {
+        if counts[l] == 0 || !target.starts_with(labels[l].as_str()) {
+            return false;
+        }
+        counts[l] -= 1;
+        let found = spellable(&target[labels[l].len()..], labels, counts, stacks - 1);
+        counts[l] += 1;
+        found
+    })
+}
+
+// Follows the parents back to the starting arrangement
+fn moves_to(nodes: &[Node], mut at: usize) -> Vec<Move> {
+    let mut moves = Vec::new();
+    while let Some((parent, m)) = nodes[at].parent {
+        moves.push(m);
+        at = parent;
+    }
+    moves.reverse();
+    moves
+}
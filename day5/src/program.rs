@@ -0,0 +1,216 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    crane::{Crane, Lift},
+    drawing::{Compact, Stacks},
+};
+
+/// A single `move N from A to B` instruction, with stacks numbered from 1 as in the puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_ascii_whitespace().collect();
+        let ["move", count, "from", from, "to", to] = words[..] else {
+            return Err(format!("'{}' is not like 'move 1 from 2 to 3'", s));
+        };
+        let parse = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| format!("'{}' is not a number", n))
+        };
+        Ok(Move {
+            count: parse(count)?,
+            from: parse(from)?,
+            to: parse(to)?,
+        })
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// The instructions with the input line each one came from.
+pub struct Program {
+    pub moves: Vec<(usize, Move)>,
+}
+
+impl Program {
+    /// Parses the instruction lines, `first_line` being the input line number of the first one.
+    pub fn parse(
+        lines: impl Iterator<Item = String>,
+        first_line: usize,
+    ) -> Result<Program, String> {
+        let moves = lines
+            .enumerate()
+            .map(|(i, l)| {
+                l.parse()
+                    .map(|m| (first_line + i, m))
+                    .map_err(|e| format!("line {}: {}", first_line + i, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Program { moves })
+    }
+
+    /// Checks that every move names existing stacks and never takes more crates than its source
+    /// stack holds at that point. Only the stack heights matter here, so this holds for any
+    /// crane.
+    pub fn validate(&self, stacks: &Stacks) -> Result<(), String> {
+        let mut heights: Vec<usize> = stacks.iter().map(|s| s.len()).collect();
+        for (line, m) in &self.moves {
+            for stack in [m.from, m.to] {
+                if stack == 0 || stack > heights.len() {
+                    return Err(format!(
+                        "line {}: there is no stack {}, only 1 to {}",
+                        line,
+                        stack,
+                        heights.len()
+                    ));
+                }
+            }
+            if heights[m.from - 1] < m.count {
+                return Err(format!(
+                    "line {}: can't move {} crates from stack {} holding {}",
+                    line,
+                    m.count,
+                    m.from,
+                    heights[m.from - 1]
+                ));
+            }
+            heights[m.from - 1] -= m.count;
+            heights[m.to - 1] += m.count;
+        }
+        Ok(())
+    }
+}
+
+// Moves a lift's worth of crates from one stack to another, both counted from 0. Setting the
+// crates back down where they were taken from leaves the stack as it was.
+fn lift<T>(stacks: &mut [Vec<T>], from: usize, to: usize, lift: Lift) {
+    if from == to {
+        return;
+    }
+    let (source, target) = if from < to {
+        let (left, right) = stacks.split_at_mut(to);
+        (&mut left[from], &mut right[0])
+    } else {
+        let (left, right) = stacks.split_at_mut(from);
+        (&mut right[0], &mut left[to])
+    };
+    let moved = source.drain(source.len() - lift.size..);
+    if lift.reversed {
+        target.extend(moved.rev());
+    } else {
+        target.extend(moved);
+    }
+}
+
+/// Carries out a move that fits the stacks with the given crane as its `step`-th move, and
+/// returns the cost. Works on any kind of crate so that searches can use compact ones.
+pub fn apply<T>(stacks: &mut [Vec<T>], crane: &dyn Crane, step: usize, m: &Move) -> u64 {
+    let lifts = crane.lifts(step, m.count);
+    for l in &lifts {
+        lift(stacks, m.from - 1, m.to - 1, *l);
+    }
+    crane.cost(&lifts)
+}
+
+/// Runs a validated program one move at a time with the given crane, keeping count of the cost.
+/// A move only touches the top crates of two stacks, so it is undone by lifting the same
+/// crates back in the opposite order and no copies of earlier states are needed.
+pub struct Session<'a> {
+    program: &'a Program,
+    crane: &'a dyn Crane,
+    stacks: Compact,
+    // Number of moves applied so far
+    done: usize,
+    cost: u64,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(program: &'a Program, stacks: Stacks, crane: &'a dyn Crane) -> Session<'a> {
+        Session {
+            program,
+            crane,
+            stacks: Compact::new(&stacks),
+            done: 0,
+            cost: 0,
+        }
+    }
+
+    pub fn stacks(&self) -> Stacks {
+        self.stacks.expand()
+    }
+
+    pub fn tops(&self) -> String {
+        self.stacks.tops()
+    }
+
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    /// Applies the next move and returns it with its line, or `None` at the end of the program.
+    pub fn redo(&mut self) -> Option<(usize, Move)> {
+        let (line, m) = *self.program.moves.get(self.done)?;
+        self.cost += apply(&mut self.stacks.stacks, self.crane, self.done, &m);
+        self.done += 1;
+        Some((line, m))
+    }
+
+    /// Reverts the last applied move and returns it, or `None` at the start of the program.
+    pub fn undo(&mut self) -> Option<(usize, Move)> {
+        self.done = self.done.checked_sub(1)?;
+        let (line, m) = self.program.moves[self.done];
+        let lifts = self.crane.lifts(self.done, m.count);
+        for l in lifts.iter().rev() {
+            lift(&mut self.stacks.stacks, m.to - 1, m.from - 1, *l);
+        }
+        self.cost -= self.crane.cost(&lifts);
+        Some((line, m))
+    }
+
+    /// Rewinds or advances until exactly `target` moves are applied, stopping early at the end.
+    pub fn seek(&mut self, target: usize) {
+        while self.done > target {
+            self.undo();
+        }
+        while self.done < target && self.redo().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{CrateMover9000, CrateMover9001};
+
+    #[test]
+    fn move_onto_same_stack_changes_nothing() {
+        let stacks: Stacks = vec![
+            vec!["Z".to_string(), "A".to_string()],
+            vec!["B".to_string(), "C".to_string()],
+        ];
+        let program = Program::parse(["move 2 from 1 to 1".to_string()].into_iter(), 1).unwrap();
+        program.validate(&stacks).unwrap();
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let mut session = Session::new(&program, stacks.clone(), crane);
+            session.seek(usize::MAX);
+            assert_eq!(session.tops(), "AC");
+            session.seek(0);
+            assert_eq!(session.stacks(), stacks);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    io::{self, BufRead, BufReader, ErrorKind, Read},
+};
+
+use crate::marker::Window;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Packet,
+    Message,
+}
+
+impl Kind {
+    pub fn size(&self) -> usize {
+        match self {
+            Kind::Packet => 4,
+            Kind::Message => 14,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Packet => write!(f, "packet"),
+            Kind::Message => write!(f, "message"),
+        }
+    }
+}
+
+/// A marker and the bytes after it up to the next marker of the same kind. Offsets count bytes
+/// from the start of the stream, starting at 0.
+pub struct Frame {
+    pub kind: Kind,
+    pub marker: u64,
+    // Where the payload starts, right after the marker
+    pub offset: u64,
+    pub payload: Vec<u8>,
+    // Whether another marker follows, rather than the end of the stream
+    pub terminated: bool,
+}
+
+// Frame detection for one kind of marker
+struct Lane {
+    kind: Kind,
+    window: Window,
+    // The frame being read, if its marker has been seen
+    open: Option<Frame>,
+}
+
+/// Splits a stream into frames of both kinds in a single pass. Markers of one kind never
+/// overlap: the search for the next one starts after the previous one ends, so a payload runs
+/// from the end of one marker to the start of the next. Anything before the first marker of a
+/// kind isn't part of a frame.
+pub struct Decoder<R> {
+    reader: R,
+    lanes: [Lane; 2],
+    // Bytes read so far
+    pos: u64,
+    // Frames completed but not yet returned, in the order they were completed
+    ready: VecDeque<Frame>,
+    finished: bool,
+}
+
+impl<R: Read> Decoder<BufReader<R>> {
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            reader: BufReader::new(reader),
+            lanes: [Kind::Packet, Kind::Message].map(|kind| Lane {
+                kind,
+                window: Window::new(kind.size()),
+                open: None,
+            }),
+            pos: 0,
+            ready: VecDeque::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<R: BufRead> Decoder<R> {
+    fn push(&mut self, byte: u8) {
+        self.pos += 1;
+        for lane in &mut self.lanes {
+            if let Some(frame) = &mut lane.open {
+                frame.payload.push(byte);
+            }
+            if !lane.window.push(byte) {
+                continue;
+            }
+            let marker = self.pos - lane.window.size() as u64;
+            if let Some(mut frame) = lane.open.take() {
+                // The new marker's bytes went into the payload as well
+                frame.payload.truncate((marker - frame.offset) as usize);
+                frame.terminated = true;
+                self.ready.push_back(frame);
+            }
+            lane.open = Some(Frame {
+                kind: lane.kind,
+                marker,
+                offset: self.pos,
+                payload: Vec::new(),
+                terminated: false,
+            });
+            lane.window.reset();
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Decoder<R> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.ready.is_empty() && !self.finished {
+            let buf = match self.reader.fill_buf() {
+                Ok([]) => {
+                    // The last frame of each kind runs to the end of the stream
+                    self.finished = true;
+                    let open = self.lanes.iter_mut().filter_map(|l| l.open.take());
+                    self.ready.extend(open);
+                    break;
+                }
+                Ok(buf) => buf.to_vec(),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            self.reader.consume(buf.len());
+            for byte in buf {
+                self.push(byte);
+            }
+        }
+        self.ready.pop_front().map(Ok)
+    }
+}
@@ -1,42 +1,109 @@
+mod frame;
+mod marker;
+
 use std::{
-    collections::{HashMap, VecDeque},
-    io::stdin,
+    env,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
 };
 
+use frame::Decoder;
+use marker::Markers;
+
 fn main() {
-    let mut lines = stdin().lines();
+    let mut scan: Option<usize> = None;
+    let mut frames = false;
+    let mut tolerance: Option<usize> = None;
+    let mut path: Option<String> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "markers" => {
+                scan = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|n| *n > 0)
+                        .expect("markers needs a window size of at least 1"),
+                )
+            }
+            "frames" => frames = true,
+            "tolerance" => {
+                tolerance = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("tolerance needs a number of repeated characters"),
+                )
+            }
+            "file" => path = Some(args.next().expect("file needs a path")),
+            _ => panic!(
+                "Unknown argument {}, expected markers N, frames, tolerance K or file PATH",
+                arg
+            ),
+        }
+    }
 
-    while let Some(Ok(line)) = lines.next() {
-        println!("{}", process_signal(line.clone(), 4).unwrap());
-        println!("{}", process_signal(line.clone(), 14).unwrap());
+    // The raw stream is scanned as it is read, newlines and all
+    let reader = || -> Box<dyn Read> {
+        match &path {
+            Some(path) => Box::new(File::open(path).unwrap_or_else(|e| panic!("{}: {}", path, e))),
+            None => Box::new(io::stdin().lock()),
+        }
+    };
+    if let Some(size) = scan {
+        let mut out = BufWriter::new(io::stdout().lock());
+        for marker in Markers::new(reader(), size) {
+            writeln!(out, "{}", marker.unwrap()).unwrap();
+        }
+        return;
+    }
+    if frames {
+        let mut out = BufWriter::new(io::stdout().lock());
+        for frame in Decoder::new(reader()) {
+            let frame = frame.unwrap();
+            writeln!(
+                out,
+                "{} marker at {}, payload at {}, {} bytes{}: {:?}",
+                frame.kind,
+                frame.marker,
+                frame.offset,
+                frame.payload.len(),
+                if frame.terminated { "" } else { " to the end" },
+                String::from_utf8_lossy(&frame.payload)
+            )
+            .unwrap();
+        }
+        return;
     }
-}
 
-fn process_signal(line: String, num_distinct: usize) -> Option<usize> {
-    let mut it = line.char_indices();
-    let mut queue: VecDeque<char> = VecDeque::new();
-    let mut map: HashMap<char, usize> = HashMap::new();
+    let mut lines = aoc_input::lines();
 
-    while let Some((i, char)) = it.next() {
-        if let Some(num) = map.get_mut(&char) {
-            *num += 1;
-        } else {
-            map.insert(char, 1);
-        }
-        queue.push_front(char);
-
-        if queue.len() > num_distinct {
-            let rem = queue.pop_back().unwrap();
-            let num = map.get_mut(&rem).unwrap();
-            if *num == 1 {
-                map.remove(&rem);
-            } else {
-                *num -= 1;
+    while let Some(Ok(line)) = lines.next() {
+        match tolerance {
+            None => {
+                println!("{}", process_signal(&line, 4).unwrap());
+                println!("{}", process_signal(&line, 14).unwrap());
             }
-            if map.len() == num_distinct {
-                return Some(i + 1);
+            Some(k) => {
+                println!("{}", process_noisy_signal(&line, 4, k));
+                println!("{}", process_noisy_signal(&line, 14, k));
             }
         }
     }
-    return None;
+}
+
+fn process_signal(line: &str, num_distinct: usize) -> Option<usize> {
+    Markers::new(line.as_bytes(), num_distinct)
+        .next()
+        .map(|m| m.unwrap() as usize)
+}
+
+fn process_noisy_signal(line: &str, num_distinct: usize, tolerance: usize) -> String {
+    match marker::find_noisy(line.as_bytes(), num_distinct, tolerance).unwrap() {
+        Some(m) if m.found => m.end.to_string(),
+        Some(m) => format!(
+            "none, best is {} with {} of {} distinct",
+            m.end, m.distinct, num_distinct
+        ),
+        None => format!("none, the signal is shorter than {}", num_distinct),
+    }
 }
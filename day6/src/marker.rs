@@ -0,0 +1,165 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Read};
+
+/// The last `size` bytes of a stream with a count of every byte value in them, so sliding the
+/// window by one byte is a constant amount of work.
+pub struct Window {
+    size: usize,
+    // Ring buffer of the bytes in the window, the oldest at `slot` once it is full
+    ring: Vec<u8>,
+    slot: usize,
+    counts: [u32; 256],
+    distinct: usize,
+    // Bytes pushed so far
+    seen: u64,
+}
+
+impl Window {
+    pub fn new(size: usize) -> Window {
+        assert!(size > 0, "Window size must be at least 1");
+        Window {
+            size,
+            ring: vec![0; size],
+            slot: 0,
+            counts: [0; 256],
+            distinct: 0,
+            seen: 0,
+        }
+    }
+
+    /// Slides the window over the next byte and returns whether all bytes in it now differ.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.seen >= self.size as u64 {
+            let old = self.ring[self.slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        self.ring[self.slot] = byte;
+        self.slot += 1;
+        if self.slot == self.size {
+            self.slot = 0;
+        }
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.seen += 1;
+        self.is_marker()
+    }
+
+    /// Empties the window, so the next marker is made up of bytes pushed from now on.
+    pub fn reset(&mut self) {
+        *self = Window::new(self.size);
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_marker(&self) -> bool {
+        self.distinct == self.size
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.seen >= self.size as u64
+    }
+
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+}
+
+/// Every marker in a stream, given as the number of bytes read up to and including the marker.
+/// Overlapping markers are all reported, so a run of distinct bytes longer than the window
+/// yields one marker per extra byte.
+pub struct Markers<R> {
+    reader: R,
+    window: Window,
+}
+
+impl<R: Read> Markers<BufReader<R>> {
+    pub fn new(reader: R, size: usize) -> Self {
+        Markers::from_buf_read(BufReader::new(reader), size)
+    }
+}
+
+impl<R: BufRead> Markers<R> {
+    pub fn from_buf_read(reader: R, size: usize) -> Self {
+        Markers {
+            reader,
+            window: Window::new(size),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Markers<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let buf = match self.reader.fill_buf() {
+                Ok([]) => return None,
+                Ok(buf) => buf,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            // Only the bytes up to a marker are consumed, the rest are left for the next call
+            let mut used = 0;
+            let mut found = false;
+            for byte in buf {
+                used += 1;
+                if self.window.push(*byte) {
+                    found = true;
+                    break;
+                }
+            }
+            self.reader.consume(used);
+            if found {
+                return Some(Ok(self.window.seen()));
+            }
+        }
+    }
+}
+
+/// A full window with as many distinct bytes as it can have within the tolerance, or the one
+/// with the most distinct bytes, the earliest winning ties.
+pub struct Noisy {
+    // Bytes read up to and including the window
+    pub end: u64,
+    pub distinct: usize,
+    pub found: bool,
+}
+
+/// Finds the first window of `size` bytes in which at most `tolerance` bytes repeat an
+/// earlier one, that is with at least `size - tolerance` distinct bytes. Without one, gives
+/// the best window there is, or `None` if the stream is shorter than a window.
+pub fn find_noisy<R: Read>(reader: R, size: usize, tolerance: usize) -> io::Result<Option<Noisy>> {
+    let needed = size.saturating_sub(tolerance);
+    let mut window = Window::new(size);
+    let mut best: Option<Noisy> = None;
+    for byte in BufReader::new(reader).bytes() {
+        window.push(byte?);
+        if !window.is_full()
+            || best
+                .as_ref()
+                .is_some_and(|b| b.distinct >= window.distinct())
+        {
+            continue;
+        }
+        let found = window.distinct() >= needed;
+        best = Some(Noisy {
+            end: window.seen(),
+            distinct: window.distinct(),
+            found,
+        });
+        if found {
+            break;
+        }
+    }
+    Ok(best)
+}
@@ -0,0 +1,238 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+/// Index of a node in the filesystem arena.
+pub type NodeId = usize;
+
+pub enum Kind {
+    File,
+    Dir { children: BTreeMap<String, NodeId> },
+}
+
+/// Why a path couldn't be followed, with the path up to the part that failed.
+#[derive(Debug)]
+pub enum PathError {
+    Missing(String),
+    NotADir(String),
+    AboveRoot,
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::Missing(path) => write!(f, "{} doesn't exist", path),
+            PathError::NotADir(path) => write!(f, "{} is a file, not a directory", path),
+            PathError::AboveRoot => write!(f, "there is nothing above /"),
+        }
+    }
+}
+
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+    // Size of a file, or the cached total of everything below a directory
+    size: u64,
+}
+
+/// Every file and directory lives in one vector and refers to others by index, so there are no
+/// reference counted cycles between parents and children. Directory totals are kept up to date
+/// whenever a file changes, by walking up its ancestors.
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Dir {
+                    children: BTreeMap::new(),
+                },
+                size: 0,
+            }],
+        }
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.nodes[id].name
+    }
+
+    /// Entries of a directory in name order, nothing for a file.
+    pub fn children(&self, dir: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[dir].kind {
+            Kind::Dir { children } => Some(children.values().copied()),
+            Kind::File => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.nodes[id].size
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, Kind::Dir { .. })
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[dir].kind {
+            Kind::Dir { children } => children.get(name).copied(),
+            Kind::File => None,
+        }
+    }
+
+    /// Follows a path like `/a/b`, `a/b` or `../c` from `cwd`.
+    pub fn resolve(&self, cwd: NodeId, path: &str) -> Result<NodeId, PathError> {
+        let mut at = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            cwd
+        };
+        for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            if !self.is_dir(at) {
+                return Err(PathError::NotADir(self.path(at)));
+            }
+            at = match part {
+                ".." => self.nodes[at].parent.ok_or(PathError::AboveRoot)?,
+                name => self.child(at, name).ok_or_else(|| {
+                    PathError::Missing(self.path(at).trim_end_matches('/').to_string() + "/" + name)
+                })?,
+            };
+        }
+        Ok(at)
+    }
+
+    /// Absolute path of a node.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut parts = Vec::new();
+        let mut at = id;
+        while let Some(parent) = self.nodes[at].parent {
+            parts.push(self.nodes[at].name.as_str());
+            at = parent;
+        }
+        parts.reverse();
+        format!("/{}", parts.join("/"))
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Every directory still in the tree, the root first and each one before its contents.
+    pub fn dirs(&self) -> Vec<NodeId> {
+        let mut dirs = Vec::new();
+        let mut todo = vec![FileSystem::ROOT];
+        while let Some(dir) = todo.pop() {
+            dirs.push(dir);
+            todo.extend(self.children(dir).filter(|c| self.is_dir(*c)));
+        }
+        dirs
+    }
+
+    /// Whether `node` is `ancestor` or somewhere below it.
+    pub fn is_within(&self, node: NodeId, ancestor: NodeId) -> bool {
+        let mut at = Some(node);
+        while let Some(n) = at {
+            if n == ancestor {
+                return true;
+            }
+            at = self.nodes[n].parent;
+        }
+        false
+    }
+
+    fn insert(&mut self, dir: NodeId, name: &str, kind: Kind, size: u64) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(dir),
+            kind,
+            size: 0,
+        });
+        if let Kind::Dir { children } = &mut self.nodes[dir].kind {
+            children.insert(name.to_string(), id);
+        }
+        self.resize(id, size);
+        id
+    }
+
+    // Changes the totals of `from` and every directory above it from counting `old` bytes of
+    // something below them to counting `new`
+    fn update_totals(&mut self, from: Option<NodeId>, old: u64, new: u64) {
+        let mut at = from;
+        while let Some(node) = at {
+            self.nodes[node].size = self.nodes[node].size - old + new;
+            at = self.nodes[node].parent;
+        }
+    }
+
+    // Sets a node's size and updates the totals of every directory above it
+    fn resize(&mut self, id: NodeId, size: u64) {
+        let old = self.nodes[id].size;
+        self.update_totals(Some(id), old, size);
+    }
+
+    // Takes a node out of its directory, leaving it and its contents unreachable
+    fn detach(&mut self, id: NodeId) {
+        let Some(parent) = self.nodes[id].parent.take() else {
+            return;
+        };
+        let name = self.nodes[id].name.clone();
+        if let Kind::Dir { children } = &mut self.nodes[parent].kind {
+            children.remove(&name);
+        }
+        let size = self.nodes[id].size;
+        self.update_totals(Some(parent), size, 0);
+    }
+
+    /// Removes a file, or a directory with everything in it.
+    pub fn remove(&mut self, id: NodeId) {
+        self.detach(id);
+    }
+
+    /// Moves a node into `dir` under a new name. The name must be free and `dir` must not be
+    /// inside the node.
+    pub fn rename(&mut self, id: NodeId, dir: NodeId, name: &str) {
+        self.detach(id);
+        self.nodes[id].name = name.to_string();
+        self.nodes[id].parent = Some(dir);
+        if let Kind::Dir { children } = &mut self.nodes[dir].kind {
+            children.insert(name.to_string(), id);
+        }
+        let size = self.nodes[id].size;
+        self.update_totals(Some(dir), 0, size);
+    }
+
+    /// Adds a directory, or returns the one already there by that name.
+    pub fn add_dir(&mut self, dir: NodeId, name: &str) -> NodeId {
+        match self.child(dir, name) {
+            Some(id) => id,
+            None => self.insert(
+                dir,
+                name,
+                Kind::Dir {
+                    children: BTreeMap::new(),
+                },
+                0,
+            ),
+        }
+    }
+
+    /// Adds a file, or sets the size of the one already there by that name. A directory by that
+    /// name is left as it is.
+    pub fn add_file(&mut self, dir: NodeId, name: &str, size: u64) -> NodeId {
+        match self.child(dir, name) {
+            Some(id) => {
+                if !self.is_dir(id) {
+                    self.resize(id, size);
+                }
+                id
+            }
+            None => self.insert(dir, name, Kind::File, size),
+        }
+    }
+}
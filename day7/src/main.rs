@@ -1,149 +1,175 @@
-use std::{cell::RefCell, collections::HashMap, io::stdin, rc::Rc};
+mod fs;
+mod plan;
+mod report;
+mod shell;
 
-enum Ent {
-    File { file: File },
-    Dir { dir: Rc<RefCell<Dir>> },
-}
-
-struct File {
-    size: u32,
-}
+use std::{env, process::exit};
 
-struct Dir {
-    sub: HashMap<String, Ent>,
-    parent: Option<Rc<RefCell<Dir>>>,
-}
+use fs::{FileSystem, NodeId};
+use plan::Constraints;
+use shell::Shell;
 
-impl Ent {
-    fn get_size(&self) -> u32 {
-        match self {
-            Ent::File { file } => file.size,
-            Ent::Dir { dir } => dir.borrow().sub.values().map(|e| e.get_size()).sum(),
+fn main() {
+    let mut check = false;
+    let mut show_tree = false;
+    let mut du = false;
+    let mut depth: Option<usize> = None;
+    let mut json = false;
+    let mut plan = false;
+    let mut protect: Vec<String> = Vec::new();
+    let mut max_deletions: Option<usize> = None;
+    let mut need: Option<u64> = None;
+    let mut limit = 1_000_000;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "check" => check = true,
+            "tree" => show_tree = true,
+            "du" => du = true,
+            "depth" => {
+                depth = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("depth needs a number"),
+                )
+            }
+            "json" => json = true,
+            "plan" => plan = true,
+            "protect" => protect.push(args.next().expect("protect needs a path")),
+            "max" => {
+                max_deletions = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("max needs a number of directories"),
+                )
+            }
+            "need" => {
+                need = Some(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .expect("need needs a number of bytes"),
+                )
+            }
+            "limit" => {
+                limit = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| *n > 0)
+                    .expect("limit needs a positive number")
+            }
+            _ => panic!(
+                "Unknown argument {}, expected check, tree, du, depth N, json, plan, protect PATH, max N, need N or limit N",
+                arg
+            ),
         }
     }
 
-    fn sum_dir_below_size(&self, at_most: u32) -> u32 {
-        let self_size = self.get_size();
-        let mut tot_size = 0;
-        tot_size += if self_size <= at_most { self_size } else { 0 };
+    let mut shell = Shell::new();
+    let mut n = 0;
+    let mut lines = aoc_input::lines();
+    while let Some(Ok(line)) = lines.next() {
+        n += 1;
+        shell.run(n, &line);
+    }
+    let (fs, errors, warnings) = shell.finish();
+    if check {
+        for error in &errors {
+            println!("error: {}", error);
+        }
+        for warning in &warnings {
+            println!("warning: {}", warning);
+        }
+        println!("{} errors, {} warnings", errors.len(), warnings.len());
+        if !errors.is_empty() {
+            exit(1);
+        }
+        return;
+    }
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("error: {}", error);
+        }
+        exit(1);
+    }
 
-        match self {
-            Ent::File { file: _ } => return 0,
-            Ent::Dir { dir } => {
-                tot_size += dir
-                    .borrow()
-                    .sub
-                    .values()
-                    .map(|e| e.sum_dir_below_size(at_most))
-                    .sum::<u32>()
-            }
-        };
-        tot_size
+    if show_tree {
+        print!("{}", report::tree(&fs));
+    }
+    if du {
+        for (size, path) in report::du(&fs, depth) {
+            println!("{}\t{}", size, path);
+        }
+    }
+    if json {
+        print!("{}", report::json(&fs));
+    }
+    if show_tree || du || json {
+        return;
     }
 
-    fn smallest_dir_at_least(&self, at_least: u32) -> u32 {
-        let self_size = match self.get_size().checked_sub(at_least) {
-            Some(s) => s,
-            None => u32::MAX - at_least,
-        } + at_least;
+    // Solution for part 1
+    println!("{}", sum_dir_below_size(&fs, 100000));
 
-        let min_dir = match self {
-            Ent::File { file: _ } => return u32::MAX,
-            Ent::Dir { dir } => dir
-                .borrow()
-                .sub
-                .values()
-                .map(|e| e.smallest_dir_at_least(at_least))
-                .min()
-                .unwrap(),
+    // Solution for part 2
+    let root_size = fs.size(FileSystem::ROOT);
+    let needed_space = (30000000 + root_size).saturating_sub(70000000);
+    println!(
+        "{}",
+        smallest_dir_at_least(&fs, needed_space).map_or(0, |dir| fs.size(dir))
+    );
+
+    if plan {
+        let protected = protect
+            .iter()
+            .map(|p| {
+                fs.resolve(FileSystem::ROOT, p)
+                    .unwrap_or_else(|e| panic!("Can't protect {}: {}", p, e))
+            })
+            .collect();
+        let constraints = Constraints {
+            protected,
+            max_deletions,
         };
-        std::cmp::min(self_size, min_dir)
+        print_plan(&fs, need.unwrap_or(needed_space), &constraints, limit);
     }
 }
 
-fn main() {
-    let root = Rc::new(RefCell::new(Dir {
-        sub: HashMap::new(),
-        parent: None,
-    }));
-    let mut current_dir = root.clone();
-
-    let mut lines = stdin().lines().peekable();
-    // Skip initial $ cd /
-    lines.next();
-    while let Some(Ok(line)) = lines.next() {
-        if line.starts_with('$') {
-            let mut cmd_split = line.split(' ');
-            match cmd_split.nth(1).unwrap() {
-                "cd" => match cmd_split.next().unwrap() {
-                    ".." => {
-                        // Move dir up
-                        let up = current_dir.borrow().parent.as_ref().unwrap().clone();
-                        current_dir = up;
-                    }
-                    s => {
-                        // Move dir down
-                        let down = match current_dir.borrow().sub.get(s).unwrap() {
-                            Ent::File { file: _ } => unimplemented!(),
-                            Ent::Dir { dir } => dir.clone(),
-                        };
-                        current_dir = down;
-                    }
-                },
-                "ls" => {
-                    while let Some(p) = lines.peek() {
-                        if !p.as_ref().unwrap().starts_with('$') {
-                            let line_entry = lines.next().unwrap().unwrap();
-                            let line_entry_split = &mut line_entry.split(' ');
-                            match line_entry_split.next().unwrap() {
-                                "dir" => {
-                                    // Add directory entry
-                                    let name = line_entry_split.next().unwrap();
-                                    let new = Dir {
-                                        sub: HashMap::new(),
-                                        parent: Some(current_dir.clone()),
-                                    };
-                                    current_dir.borrow_mut().sub.insert(
-                                        name.to_string(),
-                                        Ent::Dir {
-                                            dir: Rc::new(RefCell::new(new)),
-                                        },
-                                    );
-                                }
-                                s => {
-                                    // Add file entry
-                                    let name = line_entry_split.next().unwrap();
-                                    current_dir.borrow_mut().sub.insert(
-                                        name.to_string(),
-                                        Ent::File {
-                                            file: File {
-                                                size: s.parse::<u32>().unwrap(),
-                                            },
-                                        },
-                                    );
-                                }
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                }
-                _ => unimplemented!(),
+fn print_plan(fs: &FileSystem, needed: u64, constraints: &Constraints, limit: u64) {
+    let outcome = plan::plan(fs, needed, constraints, limit);
+    println!("Need to free {}", needed);
+    match outcome.best {
+        Some((freed, dirs)) => {
+            for dir in &dirs {
+                println!("  delete {} ({})", fs.path(*dir), fs.size(*dir));
             }
+            println!(
+                "Frees {} in {} directories, {} more than needed{}",
+                freed,
+                dirs.len(),
+                freed - needed,
+                if outcome.exhaustive {
+                    ""
+                } else {
+                    " (search limit reached, may not be optimal)"
+                }
+            );
         }
+        None if outcome.exhaustive => println!("No deletion plan frees enough space"),
+        None => println!("No deletion plan found within the search limit"),
     }
+}
 
-    // Solution for part 1
-    println!(
-        "{}",
-        Ent::Dir { dir: root.clone() }.sum_dir_below_size(100000)
-    );
+fn sum_dir_below_size(fs: &FileSystem, at_most: u64) -> u64 {
+    fs.dirs()
+        .into_iter()
+        .map(|dir| fs.size(dir))
+        .filter(|size| *size <= at_most)
+        .sum()
+}
 
-    // Solution for part 2
-    let root_size = Ent::Dir { dir: root.clone() }.get_size();
-    let needed_space = 30000000 + root_size - 70000000;
-    println!(
-        "{}",
-        Ent::Dir { dir: root.clone() }.smallest_dir_at_least(needed_space)
-    );
+fn smallest_dir_at_least(fs: &FileSystem, at_least: u64) -> Option<NodeId> {
+    fs.dirs()
+        .into_iter()
+        .filter(|dir| fs.size(*dir) >= at_least)
+        .min_by_key(|dir| fs.size(*dir))
 }
@@ -0,0 +1,221 @@
+use std::cmp::Reverse;
+
+use crate::fs::{FileSystem, NodeId};
+
+pub struct Constraints {
+    // Directories and files that must survive with everything in them
+    pub protected: Vec<NodeId>,
+    pub max_deletions: Option<usize>,
+}
+
+pub struct Outcome {
+    // Directories to delete and the space that frees
+    pub best: Option<(u64, Vec<NodeId>)>,
+    // False if the search stopped at its limit. Otherwise no other choice of directories frees
+    // enough while deleting less, and `None` means the constraints can't be met at all.
+    pub exhaustive: bool,
+}
+
+/// Branch and bound over the directories from largest to smallest, either deleting each one
+/// or not. Deleting a directory rules out everything above and below it, which is tracked
+/// incrementally along with the space the directories still open could free.
+struct Search<'a> {
+    // Candidate directories, largest first, with their sizes
+    dirs: Vec<(NodeId, u64)>,
+    // Position of every node in a depth-first walk and the position after its last descendant
+    enter: &'a [usize],
+    leave: &'a [usize],
+    // The nodes in that order, the parent of each and the candidate each one is, if any
+    by_order: &'a [NodeId],
+    parent: &'a [Option<NodeId>],
+    candidate: &'a [Option<usize>],
+    needed: u64,
+    max_deletions: usize,
+    // Number of chosen directories above or below each candidate, it is open while this is zero
+    blocked: Vec<u32>,
+    // Total size of the open candidates not yet decided on
+    open: u64,
+    chosen: Vec<usize>,
+    freed: u64,
+    best: Option<(u64, Vec<usize>)>,
+    nodes: u64,
+    limit: u64,
+    aborted: bool,
+}
+
+impl Search<'_> {
+    // Blocks or reopens everything above and below a candidate. Candidates after it are still
+    // to be decided on, so they also count towards `open`.
+    fn set_chosen(&mut self, dir: usize, chosen: bool) {
+        let node = self.dirs[dir].0;
+        let (candidate, parent) = (self.candidate, self.parent);
+        let below = self.by_order[self.enter[node] + 1..self.leave[node]]
+            .iter()
+            .copied();
+        let above = std::iter::successors(parent[node], |p| parent[*p]);
+        for r in below.chain(above).filter_map(|n| candidate[n]) {
+            let change = if chosen {
+                self.blocked[r] += 1;
+                self.blocked[r] == 1
+            } else {
+                self.blocked[r] -= 1;
+                self.blocked[r] == 0
+            };
+            if change && r > dir {
+                if chosen {
+                    self.open -= self.dirs[r].1;
+                } else {
+                    self.open += self.dirs[r].1;
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, next: usize) {
+        // Every candidate passed over here is taken out of `open`, so it's put back on the way out
+        let open = self.open;
+        self.decide_from(next);
+        self.open = open;
+    }
+
+    // Deletes or keeps each open candidate from `next` on, recursing for deletions and looping
+    // for the rest so the stack only grows with the number of deletions
+    fn decide_from(&mut self, mut next: usize) {
+        loop {
+            // Nothing beats freeing exactly what's needed
+            if self.best.as_ref().is_some_and(|(b, _)| *b == self.needed) {
+                return;
+            }
+            self.nodes += 1;
+            if self.nodes > self.limit {
+                self.aborted = true;
+                return;
+            }
+            if self.freed >= self.needed {
+                // Deleting more only frees more, so this can't be improved upon
+                if self.best.as_ref().is_none_or(|(b, _)| self.freed < *b) {
+                    self.best = Some((self.freed, self.chosen.clone()));
+                }
+                return;
+            }
+            while next < self.dirs.len() && self.blocked[next] > 0 {
+                next += 1;
+            }
+            if next == self.dirs.len() || self.chosen.len() == self.max_deletions {
+                return;
+            }
+            if self.best.as_ref().is_some_and(|(b, _)| self.freed >= *b) {
+                return;
+            }
+            // Even deleting every open directory that's left isn't enough
+            if self.freed + self.open < self.needed {
+                return;
+            }
+
+            let size = self.dirs[next].1;
+            self.open -= size;
+            self.chosen.push(next);
+            self.freed += size;
+            self.set_chosen(next, true);
+            self.visit(next + 1);
+            self.set_chosen(next, false);
+            self.freed -= size;
+            self.chosen.pop();
+            if self.aborted {
+                return;
+            }
+            next += 1;
+        }
+    }
+}
+
+/// Picks directories, none inside another, that together free at least `needed` while
+/// deleting as little as possible. The root is never deleted. Gives up after `limit` search
+/// nodes, which must be at least one.
+pub fn plan(fs: &FileSystem, needed: u64, constraints: &Constraints, limit: u64) -> Outcome {
+    assert!(limit > 0, "The search limit must be at least 1");
+    // Number the nodes depth first, so that a node's descendants are the ones numbered from its
+    // own number up to its `leave` number
+    let mut enter = vec![0; fs.node_count()];
+    let mut leave = vec![0; fs.node_count()];
+    let mut by_order = Vec::new();
+    let mut parent = vec![None; fs.node_count()];
+    let mut todo = vec![(FileSystem::ROOT, false)];
+    while let Some((id, done)) = todo.pop() {
+        if done {
+            leave[id] = by_order.len();
+            continue;
+        }
+        enter[id] = by_order.len();
+        by_order.push(id);
+        todo.push((id, true));
+        for c in fs.children(id) {
+            parent[c] = Some(id);
+            todo.push((c, false));
+        }
+    }
+
+    // Deleting anything above a protected node takes it along
+    let protected = |dir: NodeId| {
+        constraints.protected.iter().any(|p| {
+            (enter[dir] <= enter[*p] && enter[*p] < leave[dir])
+                || (enter[*p] <= enter[dir] && enter[dir] < leave[*p])
+        })
+    };
+    // The most that can be freed is every highest directory that may be deleted
+    fn most(fs: &FileSystem, dir: NodeId, protected: &dyn Fn(NodeId) -> bool) -> u64 {
+        if dir != FileSystem::ROOT && !protected(dir) {
+            return fs.size(dir);
+        }
+        fs.children(dir)
+            .filter(|c| fs.is_dir(*c))
+            .map(|c| most(fs, c, protected))
+            .sum()
+    }
+    if most(fs, FileSystem::ROOT, &protected) < needed {
+        return Outcome {
+            best: None,
+            exhaustive: true,
+        };
+    }
+
+    let mut dirs: Vec<(NodeId, u64)> = fs
+        .dirs()
+        .into_iter()
+        .filter(|d| *d != FileSystem::ROOT && !protected(*d))
+        .map(|d| (d, fs.size(d)))
+        .collect();
+    dirs.sort_unstable_by_key(|(_, size)| Reverse(*size));
+
+    let mut candidate = vec![None; fs.node_count()];
+    for (i, (d, _)) in dirs.iter().enumerate() {
+        candidate[*d] = Some(i);
+    }
+
+    let open = dirs.iter().map(|(_, size)| size).sum();
+    let mut search = Search {
+        blocked: vec![0; dirs.len()],
+        dirs,
+        enter: &enter,
+        leave: &leave,
+        by_order: &by_order,
+        parent: &parent,
+        candidate: &candidate,
+        needed,
+        max_deletions: constraints.max_deletions.unwrap_or(usize::MAX),
+        open,
+        chosen: Vec::new(),
+        freed: 0,
+        best: None,
+        nodes: 0,
+        limit,
+        aborted: false,
+    };
+    search.visit(0);
+    Outcome {
+        best: search
+            .best
+            .map(|(freed, chosen)| (freed, chosen.iter().map(|i| search.dirs[*i].0).collect())),
+        exhaustive: !search.aborted,
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt::Write;
+
+use crate::fs::{FileSystem, NodeId};
+
+/// The whole hierarchy drawn like the `tree` command, with every directory's total.
+pub fn tree(fs: &FileSystem) -> String {
+    fn entries(fs: &FileSystem, dir: NodeId, prefix: &str, out: &mut String) {
+        let children: Vec<NodeId> = fs.children(dir).collect();
+        for (i, child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            let branch = if last { "└── " } else { "├── " };
+            if fs.is_dir(*child) {
+                writeln!(
+                    out,
+                    "{}{}{}/ ({})",
+                    prefix,
+                    branch,
+                    fs.name(*child),
+                    fs.size(*child)
+                )
+                .unwrap();
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                entries(fs, *child, &prefix, out);
+            } else {
+                writeln!(
+                    out,
+                    "{}{}{} {}",
+                    prefix,
+                    branch,
+                    fs.name(*child),
+                    fs.size(*child)
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let mut out = format!("/ ({})\n", fs.size(FileSystem::ROOT));
+    entries(fs, FileSystem::ROOT, "", &mut out);
+    out
+}
+
+/// Directories no more than `max_depth` levels below the root, largest first, like `du`.
+pub fn du(fs: &FileSystem, max_depth: Option<usize>) -> Vec<(u64, String)> {
+    let mut dirs = Vec::new();
+    let mut todo = vec![(FileSystem::ROOT, 0)];
+    while let Some((dir, depth)) = todo.pop() {
+        dirs.push((fs.size(dir), fs.path(dir)));
+        if max_depth.is_some_and(|max| depth >= max) {
+            continue;
+        }
+        todo.extend(
+            fs.children(dir)
+                .filter(|c| fs.is_dir(*c))
+                .map(|c| (c, depth + 1)),
+        );
+    }
+    // Ties are listed by path so the order doesn't depend on the transcript
+    dirs.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    dirs
+}
+
+/// The whole hierarchy as JSON. Every node has a name, type and size, where a directory's size
+/// is its total, and directories also have their children.
+pub fn json(fs: &FileSystem) -> String {
+    fn node(fs: &FileSystem, id: NodeId, indent: usize, out: &mut String) {
+        let pad = "  ".repeat(indent);
+        let name = if id == FileSystem::ROOT {
+            "/"
+        } else {
+            fs.name(id)
+        };
+        write!(out, "{}{{\"name\": {}, ", pad, quote(name)).unwrap();
+        if !fs.is_dir(id) {
+            write!(out, "\"type\": \"file\", \"size\": {}}}", fs.size(id)).unwrap();
+            return;
+        }
+        write!(
+            out,
+            "\"type\": \"dir\", \"size\": {}, \"children\": [",
+            fs.size(id)
+        )
+        .unwrap();
+        let children: Vec<NodeId> = fs.children(id).collect();
+        for (i, child) in children.iter().enumerate() {
+            out.push('\n');
+            node(fs, *child, indent + 1, out);
+            if i + 1 < children.len() {
+                out.push(',');
+            }
+        }
+        if !children.is_empty() {
+            write!(out, "\n{}", pad).unwrap();
+        }
+        out.push_str("]}");
+    }
+
+    let mut out = String::new();
+    node(fs, FileSystem::ROOT, 0, &mut out);
+    out.push('\n');
+    out
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,369 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::fs::{FileSystem, NodeId, PathError};
+
+#[derive(Debug)]
+pub enum Error {
+    UnknownCommand { line: usize, command: String },
+    Usage { line: usize, usage: &'static str },
+    // Output that doesn't follow an `ls`
+    StrayOutput { line: usize },
+    BadEntry { line: usize, entry: String },
+    BadPath { line: usize, error: PathError },
+    // A name listed as a file and as a directory
+    Conflict { line: usize, path: String },
+    BadName { line: usize, name: String },
+    Exists { line: usize, path: String },
+    // Something only files can take
+    IsADirectory { line: usize, path: String },
+    // Removing or moving the current directory or one above it
+    InUse { line: usize, path: String },
+    IntoItself { line: usize, path: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownCommand { line, command } => {
+                write!(f, "line {}: unknown command '{}'", line, command)
+            }
+            Error::Usage { line, usage } => write!(f, "line {}: usage is '{}'", line, usage),
+            Error::StrayOutput { line } => {
+                write!(f, "line {}: output without a command before it", line)
+            }
+            Error::BadEntry { line, entry } => write!(
+                f,
+                "line {}: '{}' is not like 'dir NAME' or 'SIZE NAME'",
+                line, entry
+            ),
+            Error::BadPath { line, error } => write!(f, "line {}: {}", line, error),
+            Error::Conflict { line, path } => write!(
+                f,
+                "line {}: {} is listed as both a file and a directory",
+                line, path
+            ),
+            Error::BadName { line, name } => {
+                write!(f, "line {}: '{}' can't be used as a name", line, name)
+            }
+            Error::Exists { line, path } => write!(f, "line {}: {} already exists", line, path),
+            Error::IsADirectory { line, path } => {
+                write!(f, "line {}: {} is a directory", line, path)
+            }
+            Error::InUse { line, path } => write!(
+                f,
+                "line {}: {} is the current directory or above it",
+                line, path
+            ),
+            Error::IntoItself { line, path } => {
+                write!(f, "line {}: can't move {} into itself", line, path)
+            }
+        }
+    }
+}
+
+/// Transcripts that replay fine but probably don't describe the whole filesystem.
+pub enum Warning {
+    // Its contents are unknown, so it counts as empty
+    NeverListed {
+        path: String,
+    },
+    SizeChanged {
+        line: usize,
+        path: String,
+        old: u64,
+        new: u64,
+    },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::NeverListed { path } => {
+                write!(f, "{} is never listed, its contents are unknown", path)
+            }
+            Warning::SizeChanged {
+                line,
+                path,
+                old,
+                new,
+            } => write!(
+                f,
+                "line {}: {} was listed before with size {}, now {}",
+                line, path, old, new
+            ),
+        }
+    }
+}
+
+/// Replays a transcript line by line into a filesystem. Problems are collected rather than
+/// stopping the replay, and the line they are on is skipped.
+pub struct Shell {
+    fs: FileSystem,
+    cwd: NodeId,
+    // Whether the lines being read are the output of `ls`
+    listing: bool,
+    listed: HashSet<NodeId>,
+    errors: Vec<Error>,
+    warnings: Vec<Warning>,
+}
+
+impl Shell {
+    pub fn new() -> Shell {
+        Shell {
+            fs: FileSystem::new(),
+            cwd: FileSystem::ROOT,
+            listing: false,
+            listed: HashSet::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn run(&mut self, line: usize, text: &str) {
+        let result = match text.strip_prefix("$ ") {
+            Some(command) => {
+                self.listing = false;
+                let words: Vec<&str> = command.split_whitespace().collect();
+                self.command(line, &words)
+            }
+            None if self.listing => self.entry(line, text),
+            None => Err(Error::StrayOutput { line }),
+        };
+        if let Err(e) = result {
+            self.errors.push(e);
+        }
+    }
+
+    fn command(&mut self, line: usize, words: &[&str]) -> Result<(), Error> {
+        match words {
+            ["cd", path] => {
+                let dir = self
+                    .fs
+                    .resolve(self.cwd, path)
+                    .map_err(|error| Error::BadPath { line, error })?;
+                if !self.fs.is_dir(dir) {
+                    return Err(Error::BadPath {
+                        line,
+                        error: PathError::NotADir(self.fs.path(dir)),
+                    });
+                }
+                self.cwd = dir;
+            }
+            ["cd", ..] => {
+                return Err(Error::Usage {
+                    line,
+                    usage: "cd PATH",
+                })
+            }
+            ["ls"] => {
+                self.listing = true;
+                self.listed.insert(self.cwd);
+            }
+            ["ls", ..] => return Err(Error::Usage { line, usage: "ls" }),
+            ["mkdir", path] => {
+                let (dir, name) = self.parent_and_name(line, path)?;
+                if let Some(existing) = self.fs.child(dir, &name) {
+                    return Err(Error::Exists {
+                        line,
+                        path: self.fs.path(existing),
+                    });
+                }
+                let new = self.fs.add_dir(dir, &name);
+                // A new directory is known to be empty
+                self.listed.insert(new);
+            }
+            ["mkdir", ..] => {
+                return Err(Error::Usage {
+                    line,
+                    usage: "mkdir PATH",
+                })
+            }
+            ["touch", path, size] => {
+                let size: u64 = size.parse().map_err(|_| Error::Usage {
+                    line,
+                    usage: "touch PATH SIZE",
+                })?;
+                let (dir, name) = self.parent_and_name(line, path)?;
+                if let Some(existing) = self.fs.child(dir, &name) {
+                    if self.fs.is_dir(existing) {
+                        return Err(Error::IsADirectory {
+                            line,
+                            path: self.fs.path(existing),
+                        });
+                    }
+                }
+                self.fs.add_file(dir, &name, size);
+            }
+            ["touch", ..] => {
+                return Err(Error::Usage {
+                    line,
+                    usage: "touch PATH SIZE",
+                })
+            }
+            ["rm", path] | ["rm", "-r", path] => {
+                let node = self
+                    .fs
+                    .resolve(self.cwd, path)
+                    .map_err(|error| Error::BadPath { line, error })?;
+                if self.fs.is_within(self.cwd, node) {
+                    return Err(Error::InUse {
+                        line,
+                        path: self.fs.path(node),
+                    });
+                }
+                if self.fs.is_dir(node) && words[1] != "-r" {
+                    return Err(Error::IsADirectory {
+                        line,
+                        path: self.fs.path(node),
+                    });
+                }
+                self.fs.remove(node);
+            }
+            ["rm", ..] => {
+                return Err(Error::Usage {
+                    line,
+                    usage: "rm [-r] PATH",
+                })
+            }
+            ["mv", from, to] => {
+                let node = self
+                    .fs
+                    .resolve(self.cwd, from)
+                    .map_err(|error| Error::BadPath { line, error })?;
+                if self.fs.is_within(self.cwd, node) {
+                    return Err(Error::InUse {
+                        line,
+                        path: self.fs.path(node),
+                    });
+                }
+                // Moving onto a directory puts the node inside it, like `mv` does
+                let (dir, name) = match self.fs.resolve(self.cwd, to) {
+                    Ok(target) if self.fs.is_dir(target) => {
+                        (target, self.fs.name(node).to_string())
+                    }
+                    Ok(target) => {
+                        return Err(Error::Exists {
+                            line,
+                            path: self.fs.path(target),
+                        })
+                    }
+                    Err(_) => self.parent_and_name(line, to)?,
+                };
+                if let Some(existing) = self.fs.child(dir, &name) {
+                    return Err(Error::Exists {
+                        line,
+                        path: self.fs.path(existing),
+                    });
+                }
+                if self.fs.is_within(dir, node) {
+                    return Err(Error::IntoItself {
+                        line,
+                        path: self.fs.path(node),
+                    });
+                }
+                self.fs.rename(node, dir, &name);
+            }
+            ["mv", ..] => {
+                return Err(Error::Usage {
+                    line,
+                    usage: "mv FROM TO",
+                })
+            }
+            [command, ..] => {
+                return Err(Error::UnknownCommand {
+                    line,
+                    command: command.to_string(),
+                })
+            }
+            [] => {
+                return Err(Error::UnknownCommand {
+                    line,
+                    command: String::new(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    // The existing directory a new node at `path` would go in, and its name
+    fn parent_and_name(&self, line: usize, path: &str) -> Result<(NodeId, String), Error> {
+        let path = match path.trim_end_matches('/') {
+            "" => "/",
+            p => p,
+        };
+        let (dir, name) = match path.rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((dir, name)) => (dir, name),
+            None => (".", path),
+        };
+        if name.is_empty() || name == "." || name == ".." || name == "/" {
+            return Err(Error::BadName {
+                line,
+                name: name.to_string(),
+            });
+        }
+        let dir = self
+            .fs
+            .resolve(self.cwd, dir)
+            .map_err(|error| Error::BadPath { line, error })?;
+        if !self.fs.is_dir(dir) {
+            return Err(Error::BadPath {
+                line,
+                error: PathError::NotADir(self.fs.path(dir)),
+            });
+        }
+        Ok((dir, name.to_string()))
+    }
+
+    fn entry(&mut self, line: usize, text: &str) -> Result<(), Error> {
+        let bad = || Error::BadEntry {
+            line,
+            entry: text.to_string(),
+        };
+        let (kind, name) = text.split_once(' ').ok_or_else(bad)?;
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            return Err(bad());
+        }
+        let existing = self.fs.child(self.cwd, name);
+        let conflict = || Error::Conflict {
+            line,
+            path: self.fs.path(existing.unwrap()),
+        };
+        if kind == "dir" {
+            if existing.is_some_and(|e| !self.fs.is_dir(e)) {
+                return Err(conflict());
+            }
+            self.fs.add_dir(self.cwd, name);
+            return Ok(());
+        }
+
+        let size: u64 = kind.parse().map_err(|_| bad())?;
+        if let Some(e) = existing {
+            if self.fs.is_dir(e) {
+                return Err(conflict());
+            }
+            if self.fs.size(e) != size {
+                self.warnings.push(Warning::SizeChanged {
+                    line,
+                    path: self.fs.path(e),
+                    old: self.fs.size(e),
+                    new: size,
+                });
+            }
+        }
+        self.fs.add_file(self.cwd, name, size);
+        Ok(())
+    }
+
+    /// Ends the replay, adding a warning for every directory that was never listed.
+    pub fn finish(mut self) -> (FileSystem, Vec<Error>, Vec<Warning>) {
+        for dir in self.fs.dirs() {
+            if !self.listed.contains(&dir) {
+                self.warnings.push(Warning::NeverListed {
+                    path: self.fs.path(dir),
+                });
+            }
+        }
+        (self.fs, self.errors, self.warnings)
+    }
+}
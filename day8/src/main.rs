@@ -1,5 +1,3 @@
-use std::io::stdin;
-
 const VIS_LEFT: u8 = 0b0001;
 const VIS_RIGHT: u8 = 0b0010;
 const VIS_UP: u8 = 0b0100;
@@ -8,7 +6,7 @@ const VIS_DOWN: u8 = 0b1000;
 fn main() {
     let mut map: Vec<Vec<(u8, u8, u32)>> = Vec::new();
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let chars = line.chars();
         map.push(
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, io::stdin};
+use std::collections::HashSet;
 
 fn vector_add(a: &(isize, isize), b: &(isize, isize)) -> (isize, isize) {
     (a.0 + b.0, a.1 + b.1)
@@ -66,7 +66,7 @@ fn main() {
     let (mut rope_short, mut visited_short) = setup_rope(2);
     let (mut rope_long, mut visited_long) = setup_rope(10);
 
-    let mut lines = stdin().lines();
+    let mut lines = aoc_input::lines();
     while let Some(Ok(line)) = lines.next() {
         let mut split = line.split(' ');
         let dir = split.next().unwrap().parse::<char>().unwrap();